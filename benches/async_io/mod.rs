@@ -0,0 +1,55 @@
+//! Frames written with `serialize_async` and read back with
+//! `deserialize_async` through an in-memory tokio pipe.
+
+use crate::foo::Foo;
+use criterion::{Criterion, Throughput};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::hint::black_box;
+use tokio::io::{self, AsyncWriteExt};
+use tokio::runtime::Builder;
+
+const RECORDS: usize = 100;
+
+pub fn bench(c: &mut Criterion) {
+    dataset(c, "async_foo", &Foo::default());
+    dataset(c, "async_large_vec", &vec![1337u64; 4096]);
+}
+
+fn dataset<T>(c: &mut Criterion, name: &str, value: &T)
+where
+    T: Serialize + DeserializeOwned,
+{
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    group.bench_function("serde_async_duplex", |b| {
+        let (mut writer, mut reader) = io::duplex(1 << 16);
+
+        b.iter(|| {
+            runtime.block_on(async {
+                let write = async {
+                    for _ in 0..RECORDS {
+                        let value = black_box(value);
+                        serde_bench::serialize_async(&mut writer, value)
+                            .await
+                            .unwrap();
+                    }
+                    writer.flush().await.unwrap();
+                };
+                let read = async {
+                    for _ in 0..RECORDS {
+                        let value = serde_bench::deserialize_async::<_, T>(&mut reader)
+                            .await
+                            .unwrap();
+                        black_box(value.unwrap());
+                    }
+                };
+                tokio::join!(write, read);
+            });
+        });
+    });
+
+    group.finish();
+}
@@ -3,30 +3,25 @@
 
 extern crate test;
 
+#[cfg(feature = "tokio")]
+mod async_io;
+mod bulk;
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+mod checksum;
 mod flavor;
+mod foo;
+mod format;
+mod handwritten;
+mod in_place;
+mod shapes;
+mod stream;
+mod strings;
 
 use crate::flavor::PreallocatedVec;
+use crate::foo::Foo;
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
-use serde::{Deserialize, Serialize};
 use std::hint::black_box;
 
-#[derive(Serialize, Deserialize, bincode::Encode, bincode::Decode)]
-struct Foo {
-    bar: String,
-    baz: u64,
-    derp: bool,
-}
-
-impl Default for Foo {
-    fn default() -> Self {
-        Foo {
-            bar: "hello".into(),
-            baz: 1337u64,
-            derp: true,
-        }
-    }
-}
-
 fn bincode_serde_deserialize(b: &mut Bencher) {
     let foo = Foo::default();
     let bytes = bincode::serde::encode_to_vec(&foo, bincode::config::standard()).unwrap();
@@ -134,6 +129,17 @@ fn serde_serialize(b: &mut Bencher) {
     });
 }
 
+fn serde_serialize_vec(b: &mut Bencher) {
+    let foo = Foo::default();
+    let mut bytes = Vec::new();
+
+    b.iter(|| {
+        let foo = black_box(&foo);
+        bytes.clear();
+        serde_bench::serialize_with_size_hint(&mut bytes, foo, 128).unwrap();
+    });
+}
+
 fn bench(c: &mut Criterion) {
     c.bench_function("bincode_serde_deserialize", bincode_serde_deserialize);
     c.bench_function("bincode_serde_serialize", bincode_serde_serialize);
@@ -145,7 +151,54 @@ fn bench(c: &mut Criterion) {
     c.bench_function("postcard2_serialize", postcard2_serialize);
     c.bench_function("serde_deserialize", serde_deserialize);
     c.bench_function("serde_serialize", serde_serialize);
+    c.bench_function("serde_serialize_vec", serde_serialize_vec);
+}
+
+fn bench_shapes(c: &mut Criterion) {
+    shapes::for_each(&mut shapes::Bench::new(c));
+}
+
+fn bench_in_place(c: &mut Criterion) {
+    in_place::bench(c);
+}
+
+fn bench_stream(c: &mut Criterion) {
+    stream::bench(c);
+}
+
+fn bench_bulk(c: &mut Criterion) {
+    bulk::bench(c);
+}
+
+fn bench_strings(c: &mut Criterion) {
+    strings::bench(c);
+}
+
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+fn bench_checksum(c: &mut Criterion) {
+    checksum::bench(c);
+}
+
+#[cfg(not(any(feature = "crc32fast", feature = "xxhash-rust")))]
+fn bench_checksum(_c: &mut Criterion) {}
+
+#[cfg(feature = "tokio")]
+fn bench_async(c: &mut Criterion) {
+    async_io::bench(c);
 }
 
-criterion_group!(benches, bench);
+#[cfg(not(feature = "tokio"))]
+fn bench_async(_c: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    bench,
+    bench_shapes,
+    bench_in_place,
+    bench_stream,
+    bench_checksum,
+    bench_bulk,
+    bench_strings,
+    bench_async,
+);
 criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! Large numeric datasets, encoded element by element as Serde does by
+//! default and in bulk through `serde_bench::bulk` and `serde_bytes`.
+
+use criterion::{Criterion, Throughput};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+
+#[derive(Serialize, Deserialize)]
+struct BulkF64(#[serde(with = "serde_bench::bulk")] Vec<f64>);
+
+#[derive(Serialize, Deserialize)]
+struct BulkU32(#[serde(with = "serde_bench::bulk")] Vec<u32>);
+
+#[derive(Serialize, Deserialize)]
+struct BulkArray(#[serde(with = "serde_bench::bulk::array")] [f64; 32]);
+
+#[derive(Serialize, Deserialize)]
+struct Bytes(#[serde(with = "serde_bytes")] Vec<u8>);
+
+pub fn bench(c: &mut Criterion) {
+    let floats: Vec<f64> = (0..4096).map(|i| f64::from(i) * 0.5).collect();
+    dataset(c, "bulk_vec_f64", &floats, &BulkF64(floats.clone()));
+
+    let ints: Vec<u32> = (0..16384).collect();
+    dataset(c, "bulk_vec_u32", &ints, &BulkU32(ints.clone()));
+
+    let array = [1.337f64; 32];
+    dataset(c, "bulk_array_f64", &array, &BulkArray(array));
+
+    let bytes: Vec<u8> = (0..=255).cycle().take(65536).collect();
+    dataset(c, "bulk_vec_u8", &bytes, &Bytes(bytes.clone()));
+}
+
+fn dataset<T, B>(c: &mut Criterion, name: &str, value: &T, bulk: &B)
+where
+    T: Serialize + DeserializeOwned,
+    B: Serialize + DeserializeOwned,
+{
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, value).unwrap();
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, bulk).unwrap();
+    assert_eq!(bytes, expected, "bulk encoding of {name} differs");
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    group.bench_function("serde_serialize", |b| {
+        let mut out = Vec::with_capacity(bytes.len());
+
+        b.iter(|| {
+            let value = black_box(value);
+            out.clear();
+            serde_bench::serialize(&mut out, value).unwrap();
+        });
+    });
+
+    group.bench_function("serde_serialize_bulk", |b| {
+        let mut out = Vec::with_capacity(bytes.len());
+
+        b.iter(|| {
+            let bulk = black_box(bulk);
+            out.clear();
+            serde_bench::serialize(&mut out, bulk).unwrap();
+        });
+    });
+
+    group.bench_function("serde_deserialize", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize::<T>(bytes).unwrap()
+        });
+    });
+
+    group.bench_function("serde_deserialize_bulk", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize::<B>(bytes).unwrap()
+        });
+    });
+
+    group.finish();
+}
@@ -0,0 +1,72 @@
+//! Serialization with a checksum trailer, next to plain serialization of the
+//! same data, to show what integrity checking adds.
+
+use crate::foo::Foo;
+use criterion::Criterion;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_bench::Checksum;
+use std::hint::black_box;
+
+const CHECKSUMS: &[(&str, Checksum)] = &[
+    #[cfg(feature = "crc32fast")]
+    ("crc32", Checksum::Crc32),
+    #[cfg(feature = "xxhash-rust")]
+    ("xxhash64", Checksum::XxHash64),
+];
+
+pub fn bench(c: &mut Criterion) {
+    dataset(c, "checksum_foo", &Foo::default());
+    dataset(c, "checksum_large_vec", &vec![1337u64; 4096]);
+}
+
+fn dataset<T>(c: &mut Criterion, name: &str, value: &T)
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut group = c.benchmark_group(name);
+
+    group.bench_function("serde_serialize", |b| {
+        let mut bytes = Vec::with_capacity(1 << 16);
+
+        b.iter(|| {
+            let value = black_box(value);
+            bytes.clear();
+            serde_bench::serialize(&mut bytes, value).unwrap();
+        });
+    });
+
+    group.bench_function("serde_deserialize", |b| {
+        let mut bytes = Vec::new();
+        serde_bench::serialize(&mut bytes, value).unwrap();
+
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize::<T>(bytes).unwrap()
+        });
+    });
+
+    for &(checksum_name, checksum) in CHECKSUMS {
+        group.bench_function(format!("serde_serialize_{checksum_name}"), |b| {
+            let mut bytes = Vec::with_capacity(1 << 16);
+
+            b.iter(|| {
+                let value = black_box(value);
+                bytes.clear();
+                serde_bench::serialize_with_checksum(&mut bytes, value, checksum).unwrap();
+            });
+        });
+
+        group.bench_function(format!("serde_deserialize_{checksum_name}"), |b| {
+            let mut bytes = Vec::new();
+            serde_bench::serialize_with_checksum(&mut bytes, value, checksum).unwrap();
+
+            b.iter(|| {
+                let bytes = black_box(&bytes);
+                serde_bench::deserialize_with_checksum::<T>(bytes, checksum).unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, bincode::Encode, bincode::Decode)]
+pub struct Foo {
+    pub bar: String,
+    pub baz: u64,
+    pub derp: bool,
+}
+
+impl Default for Foo {
+    fn default() -> Self {
+        Foo {
+            bar: "hello".into(),
+            baz: 1337u64,
+            derp: true,
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::flavor::PreallocatedVec;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub trait Format {
+    const NAME: &'static str;
+
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize;
+
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned;
+}
+
+pub trait Visitor {
+    fn visit<F: Format>(&mut self);
+}
+
+pub fn for_each(visitor: &mut impl Visitor) {
+    visitor.visit::<SerdeBench>();
+    visitor.visit::<BincodeSerde>();
+    visitor.visit::<Postcard>();
+    visitor.visit::<Postcard2>();
+    #[cfg(feature = "serde_json")]
+    visitor.visit::<Json>();
+    #[cfg(feature = "rmp-serde")]
+    visitor.visit::<MessagePack>();
+    #[cfg(feature = "ciborium")]
+    visitor.visit::<Cbor>();
+    #[cfg(feature = "bitcode")]
+    visitor.visit::<Bitcode>();
+    #[cfg(feature = "ron")]
+    visitor.visit::<Ron>();
+}
+
+pub struct SerdeBench;
+
+impl Format for SerdeBench {
+    const NAME: &'static str = "serde";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        serde_bench::serialize(out, value).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        serde_bench::deserialize(bytes).unwrap()
+    }
+}
+
+pub struct BincodeSerde;
+
+impl Format for BincodeSerde {
+    const NAME: &'static str = "bincode_serde";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        bincode::serde::encode_into_std_write(value, out, bincode::config::standard()).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        bincode::serde::borrow_decode_from_slice(bytes, bincode::config::standard())
+            .unwrap()
+            .0
+    }
+}
+
+pub struct Postcard;
+
+impl Format for Postcard {
+    const NAME: &'static str = "postcard";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        postcard::serialize_with_flavor(value, PreallocatedVec::new(out)).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        postcard::from_bytes(bytes).unwrap()
+    }
+}
+
+pub struct Postcard2;
+
+impl Format for Postcard2 {
+    const NAME: &'static str = "postcard2";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        postcard2::serialize_with_flavor(value, PreallocatedVec::new(out)).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        postcard2::from_bytes(bytes).unwrap()
+    }
+}
+
+#[cfg(feature = "serde_json")]
+pub struct Json;
+
+#[cfg(feature = "serde_json")]
+impl Format for Json {
+    const NAME: &'static str = "serde_json";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        serde_json::to_writer(out, value).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(bytes).unwrap()
+    }
+}
+
+#[cfg(feature = "rmp-serde")]
+pub struct MessagePack;
+
+#[cfg(feature = "rmp-serde")]
+impl Format for MessagePack {
+    const NAME: &'static str = "rmp_serde";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        rmp_serde::encode::write(out, value).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        rmp_serde::from_slice(bytes).unwrap()
+    }
+}
+
+#[cfg(feature = "ciborium")]
+pub struct Cbor;
+
+#[cfg(feature = "ciborium")]
+impl Format for Cbor {
+    const NAME: &'static str = "ciborium";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        ciborium::into_writer(value, out).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        ciborium::from_reader(bytes).unwrap()
+    }
+}
+
+/// bitcode's serde mode only serializes into a `Vec` of its own, so unlike
+/// the other formats its serialize numbers include allocating the output on
+/// every iteration.
+#[cfg(feature = "bitcode")]
+pub struct Bitcode;
+
+#[cfg(feature = "bitcode")]
+impl Format for Bitcode {
+    const NAME: &'static str = "bitcode_serde";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        *out = bitcode::serialize(value).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        bitcode::deserialize(bytes).unwrap()
+    }
+}
+
+#[cfg(feature = "ron")]
+pub struct Ron;
+
+#[cfg(feature = "ron")]
+impl Format for Ron {
+    const NAME: &'static str = "ron";
+
+    #[inline]
+    fn serialize<T>(out: &mut Vec<u8>, value: &T)
+    where
+        T: Serialize,
+    {
+        ron::Options::default().to_io_writer(out, value).unwrap();
+    }
+
+    #[inline]
+    fn deserialize<T>(bytes: &[u8]) -> T
+    where
+        T: DeserializeOwned,
+    {
+        ron::de::from_bytes(bytes).unwrap()
+    }
+}
@@ -0,0 +1,296 @@
+//! Encoders and decoders written without Serde, producing the same bytes as
+//! `serde_bench::serialize`. These are the ceiling that the Serde-driven
+//! numbers are measured against.
+
+use crate::foo::Foo;
+use crate::shapes::{Enum, UnitStruct};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::{cmp, str};
+
+pub trait Handwritten: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+#[inline]
+pub fn decode<T: Handwritten>(mut bytes: &[u8]) -> Option<T> {
+    T::decode(&mut bytes)
+}
+
+#[inline]
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Some(bytes)
+}
+
+#[inline]
+fn take_array<const N: usize>(input: &mut &[u8]) -> Option<[u8; N]> {
+    take(input, N).map(|bytes| bytes.try_into().unwrap())
+}
+
+#[inline]
+fn encode_len(out: &mut Vec<u8>, len: usize) {
+    (len as u64).encode(out);
+}
+
+#[inline]
+fn decode_len(input: &mut &[u8]) -> Option<usize> {
+    usize::try_from(u64::decode(input)?).ok()
+}
+
+macro_rules! primitive {
+    ($($ty:ty)*) => {
+        $(
+            impl Handwritten for $ty {
+                #[inline]
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_ne_bytes());
+                }
+
+                #[inline]
+                fn decode(input: &mut &[u8]) -> Option<Self> {
+                    take_array(input).map(<$ty>::from_ne_bytes)
+                }
+            }
+        )*
+    };
+}
+
+primitive!(u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64);
+
+impl Handwritten for bool {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Handwritten for char {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let width = match *input.first()? {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return None,
+        };
+        str::from_utf8(take(input, width)?).ok()?.chars().next()
+    }
+}
+
+impl Handwritten for String {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(out, self.len());
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = decode_len(input)?;
+        let bytes = take(input, len)?;
+        str::from_utf8(bytes).ok().map(str::to_owned)
+    }
+}
+
+impl<T: Handwritten> Handwritten for Option<T> {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(value) => {
+                out.push(1);
+                value.encode(out);
+            }
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(None),
+            1 => T::decode(input).map(Some),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Handwritten> Handwritten for Vec<T> {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(out, self.len());
+        for element in self {
+            element.encode(out);
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = decode_len(input)?;
+        let mut vec = Vec::with_capacity(cmp::min(len, input.len()));
+        for _ in 0..len {
+            vec.push(T::decode(input)?);
+        }
+        Some(vec)
+    }
+}
+
+impl<T: Handwritten + Copy + Default, const N: usize> Handwritten for [T; N] {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        for element in self {
+            element.encode(out);
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let mut array = [T::default(); N];
+        for element in &mut array {
+            *element = T::decode(input)?;
+        }
+        Some(array)
+    }
+}
+
+impl<K: Handwritten + Eq + Hash, V: Handwritten> Handwritten for HashMap<K, V> {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(out, self.len());
+        for (key, value) in self {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = decode_len(input)?;
+        let mut map = HashMap::with_capacity(cmp::min(len, input.len()));
+        for _ in 0..len {
+            map.insert(K::decode(input)?, V::decode(input)?);
+        }
+        Some(map)
+    }
+}
+
+impl<K: Handwritten + Ord, V: Handwritten> Handwritten for BTreeMap<K, V> {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(out, self.len());
+        for (key, value) in self {
+            key.encode(out);
+            value.encode(out);
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = decode_len(input)?;
+        let mut map = BTreeMap::new();
+        for _ in 0..len {
+            map.insert(K::decode(input)?, V::decode(input)?);
+        }
+        Some(map)
+    }
+}
+
+impl<A: Handwritten, B: Handwritten, C: Handwritten> Handwritten for (A, B, C) {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+        self.2.encode(out);
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?, C::decode(input)?))
+    }
+}
+
+impl Handwritten for Foo {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.bar.encode(out);
+        self.baz.encode(out);
+        self.derp.encode(out);
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Foo {
+            bar: String::decode(input)?,
+            baz: u64::decode(input)?,
+            derp: bool::decode(input)?,
+        })
+    }
+}
+
+impl Handwritten for Enum {
+    #[inline]
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Enum::Unit => 0u32.encode(out),
+            Enum::Newtype(v) => {
+                1u32.encode(out);
+                v.encode(out);
+            }
+            Enum::Tuple(v0, v1) => {
+                2u32.encode(out);
+                v0.encode(out);
+                v1.encode(out);
+            }
+            Enum::Struct { a, b } => {
+                3u32.encode(out);
+                a.encode(out);
+                b.encode(out);
+            }
+        }
+    }
+
+    #[inline]
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u32::decode(input)? {
+            0 => Some(Enum::Unit),
+            1 => Some(Enum::Newtype(u64::decode(input)?)),
+            2 => Some(Enum::Tuple(u64::decode(input)?, u64::decode(input)?)),
+            3 => Some(Enum::Struct {
+                a: u64::decode(input)?,
+                b: u64::decode(input)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Handwritten for UnitStruct {
+    #[inline]
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    #[inline]
+    fn decode(_input: &mut &[u8]) -> Option<Self> {
+        Some(UnitStruct)
+    }
+}
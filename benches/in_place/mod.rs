@@ -0,0 +1,57 @@
+//! Datasets dominated by heap buffers, where `deserialize_in_place` can reuse
+//! the allocations of the previous value instead of building a new one.
+
+use criterion::Criterion;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+
+#[derive(Serialize, Deserialize)]
+pub struct Record {
+    pub name: String,
+    pub samples: Vec<u64>,
+    pub tags: Vec<String>,
+}
+
+pub fn bench(c: &mut Criterion) {
+    dataset(c, "large_vec", &vec![1337u64; 4096]);
+    dataset(c, "large_string", &"serde".repeat(1024));
+    dataset(c, "vec_of_strings", &vec!["hello world".to_owned(); 256]);
+    dataset(
+        c,
+        "record",
+        &Record {
+            name: "serde".repeat(64),
+            samples: (0..1024).collect(),
+            tags: (0..64).map(|i| format!("tag{i}")).collect(),
+        },
+    );
+}
+
+fn dataset<T>(c: &mut Criterion, name: &str, value: &T)
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, value).unwrap();
+
+    let mut group = c.benchmark_group(name);
+
+    group.bench_function("serde_deserialize", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize::<T>(bytes).unwrap()
+        });
+    });
+
+    group.bench_function("serde_deserialize_in_place", |b| {
+        let mut place = serde_bench::deserialize::<T>(&bytes).unwrap();
+
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize_in_place(bytes, &mut place).unwrap();
+        });
+    });
+
+    group.finish();
+}
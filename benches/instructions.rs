@@ -0,0 +1,249 @@
+//! Counts retired instructions per iteration instead of wall-clock time, for
+//! numbers that stay comparable across commits on noisy machines.
+//!
+//! Uses the hardware instruction counter through `perf_event_open` when the
+//! kernel allows it. Otherwise each benchmark is run under Cachegrind, once
+//! with zero iterations and once with `ITERATIONS`, and the difference is
+//! reported. Passing `--save-baseline NAME` writes those counts where
+//! Criterion would have saved them, so `serde-bench-compare` reads both alike.
+
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::disallowed_names,
+    clippy::elidable_lifetime_names
+)]
+
+mod flavor;
+mod foo;
+mod format;
+mod handwritten;
+#[cfg(target_os = "linux")]
+mod perf;
+mod shapes;
+
+use crate::shapes::Shape;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+const ITERATIONS: u64 = 1000;
+const RUN_VAR: &str = "SERDE_BENCH_CACHEGRIND_RUN";
+const ITERATIONS_VAR: &str = "SERDE_BENCH_CACHEGRIND_ITERATIONS";
+
+fn main() {
+    if let Some(id) = env::var_os(RUN_VAR) {
+        let iterations = env::var(ITERATIONS_VAR).unwrap().parse().unwrap();
+        shapes::for_each(&mut Registry::Run {
+            id: id.to_str().unwrap(),
+            iterations,
+        });
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    match perf::Instructions::new() {
+        Ok(instructions) => return criterion(instructions),
+        Err(err) => eprintln!("hardware instruction counter is unavailable: {err}"),
+    }
+
+    if Command::new("valgrind")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .status()
+        .map_or(true, |status| !status.success())
+    {
+        eprintln!("valgrind is unavailable; skipping instruction counts");
+        return;
+    }
+    cachegrind();
+}
+
+#[cfg(target_os = "linux")]
+fn criterion(instructions: perf::Instructions) {
+    env::set_var("CRITERION_HOME", criterion_home());
+
+    let mut c = criterion::Criterion::default()
+        .with_measurement(instructions)
+        .configure_from_args();
+    shapes::for_each(&mut shapes::Bench::new(&mut c));
+    c.final_summary();
+}
+
+fn criterion_home() -> PathBuf {
+    // Keep instruction counts apart from the wall-clock results of the
+    // `bench` target, which use the same benchmark ids.
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    let target_dir = env::var_os("CARGO_TARGET_DIR").unwrap_or_else(|| OsString::from("target"));
+    PathBuf::from(target_dir).join("criterion-instructions")
+}
+
+// Criterion options that take a value, which must not be mistaken for the
+// filter.
+const OPTIONS_WITH_VALUE: &[&str] = &[
+    "--save-baseline",
+    "-s",
+    "--baseline",
+    "-b",
+    "--baseline-lenient",
+    "--load-baseline",
+    "--color",
+    "-c",
+    "--format",
+    "--output-format",
+    "--plotting-backend",
+    "--profile-time",
+    "--sample-size",
+    "--warm-up-time",
+    "--measurement-time",
+    "--nresamples",
+    "--noise-threshold",
+    "--confidence-level",
+    "--significance-level",
+];
+
+fn cachegrind() {
+    let mut filter = None;
+    let mut save_baseline = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if OPTIONS_WITH_VALUE.contains(&arg.as_str()) {
+            let value = args.next();
+            if arg == "--save-baseline" || arg == "-s" {
+                save_baseline = value;
+            }
+        } else if let Some(value) = arg.strip_prefix("--save-baseline=") {
+            save_baseline = Some(value.to_owned());
+        } else if !arg.starts_with('-') && filter.is_none() {
+            filter = Some(arg);
+        }
+    }
+
+    let mut ids = Vec::new();
+    shapes::for_each(&mut Registry::List(&mut ids));
+
+    let home = criterion_home();
+    for id in ids {
+        if filter.as_ref().map_or(false, |filter| !id.contains(filter)) {
+            continue;
+        }
+        let baseline = cachegrind_run(&id, 0);
+        let total = cachegrind_run(&id, ITERATIONS);
+        let per_iteration = total.saturating_sub(baseline) as f64 / ITERATIONS as f64;
+        println!("{id:<48} {per_iteration:>10.1} instr");
+        if let Some(save_baseline) = &save_baseline {
+            save(&home.join(&id).join(save_baseline), &id, per_iteration);
+        }
+    }
+}
+
+// Writes the files Criterion saves for a baseline. Cachegrind counts are
+// exact, so every estimate is the same point with no spread.
+fn save(dir: &Path, id: &str, per_iteration: f64) {
+    let (group_id, function_id) = id.split_once('/').unwrap();
+    let benchmark = serde_json::json!({
+        "group_id": group_id,
+        "function_id": function_id,
+        "value_str": null,
+        "throughput": null,
+        "full_id": id,
+        "directory_name": id,
+        "title": id,
+    });
+    let estimate = |point_estimate: f64| {
+        serde_json::json!({
+            "confidence_interval": {
+                "confidence_level": 0.95,
+                "lower_bound": point_estimate,
+                "upper_bound": point_estimate,
+            },
+            "point_estimate": point_estimate,
+            "standard_error": 0.0,
+        })
+    };
+    let estimates = serde_json::json!({
+        "mean": estimate(per_iteration),
+        "median": estimate(per_iteration),
+        "median_abs_dev": estimate(0.0),
+        "slope": null,
+        "std_dev": estimate(0.0),
+    });
+
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("benchmark.json"), benchmark.to_string()).unwrap();
+    fs::write(dir.join("estimates.json"), estimates.to_string()).unwrap();
+}
+
+fn cachegrind_run(id: &str, iterations: u64) -> u64 {
+    let out_file = env::temp_dir().join(format!("serde-bench-cachegrind.{}", process::id()));
+    let status = Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg("--cache-sim=no")
+        .arg(format!("--cachegrind-out-file={}", out_file.display()))
+        .arg(env::current_exe().unwrap())
+        .env(RUN_VAR, id)
+        .env(ITERATIONS_VAR, iterations.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "cachegrind failed on {id}");
+
+    let out = fs::read_to_string(&out_file).unwrap();
+    let _ = fs::remove_file(&out_file);
+    out.lines()
+        .find_map(|line| line.strip_prefix("summary:"))
+        .and_then(|summary| summary.split_whitespace().next())
+        .and_then(|instructions| instructions.parse().ok())
+        .unwrap_or_else(|| panic!("no summary in cachegrind output for {id}"))
+}
+
+enum Registry<'a> {
+    List(&'a mut Vec<String>),
+    Run { id: &'a str, iterations: u64 },
+}
+
+impl<'a> shapes::Visitor for Registry<'a> {
+    fn visit<T: Shape>(&mut self, name: &'static str, value: &T) {
+        struct Group<'a, 'b> {
+            registry: &'a mut Registry<'b>,
+            name: &'static str,
+        }
+
+        impl<'a, 'b> shapes::Routines for Group<'a, 'b> {
+            fn routine<O, R>(&mut self, id: &str, mut routine: R)
+            where
+                R: FnMut() -> O,
+            {
+                let id = format!("{}/{}", self.name, id);
+                match self.registry {
+                    Registry::List(ids) => ids.push(id),
+                    Registry::Run {
+                        id: run,
+                        iterations,
+                    } => {
+                        if *run == id {
+                            for _ in 0..*iterations {
+                                black_box(routine());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        shapes::routines(
+            name,
+            value,
+            &mut Group {
+                registry: self,
+                name,
+            },
+        );
+    }
+}
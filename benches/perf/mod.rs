@@ -0,0 +1,139 @@
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::Throughput;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::io::FromRawFd;
+use std::{mem, ptr};
+
+// Leading fields of `struct perf_event_attr` from <linux/perf_event.h>,
+// matching PERF_ATTR_SIZE_VER0.
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+}
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+const EXCLUDE_KERNEL: u64 = 1 << 5;
+const EXCLUDE_HV: u64 = 1 << 6;
+
+/// Counts instructions retired in user space by the current thread.
+pub struct Instructions {
+    counter: File,
+}
+
+impl Instructions {
+    pub fn new() -> io::Result<Self> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: mem::size_of::<PerfEventAttr>() as u32,
+            config: PERF_COUNT_HW_INSTRUCTIONS,
+            flags: EXCLUDE_KERNEL | EXCLUDE_HV,
+            ..PerfEventAttr::default()
+        };
+        let (pid, cpu, group_fd, flags) = (0, -1, -1, 0);
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                ptr::addr_of!(attr),
+                pid,
+                cpu,
+                group_fd,
+                flags,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let counter = unsafe { File::from_raw_fd(fd as libc::c_int) };
+        Ok(Instructions { counter })
+    }
+
+    fn read(&self) -> u64 {
+        let mut count = [0; 8];
+        (&self.counter)
+            .read_exact(&mut count)
+            .expect("failed to read instruction counter");
+        u64::from_ne_bytes(count)
+    }
+}
+
+impl Measurement for Instructions {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        self.read()
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        self.read() - start
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &InstructionsFormatter
+    }
+}
+
+struct InstructionsFormatter;
+
+impl ValueFormatter for InstructionsFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (denominator, unit) = if typical_value < 1e3 {
+            (1.0, "instr")
+        } else if typical_value < 1e6 {
+            (1e3, "Kinstr")
+        } else {
+            (1e6, "Minstr")
+        };
+        for value in values {
+            *value /= denominator;
+        }
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (per, unit) = match *throughput {
+            Throughput::Bits(bits) => (bits, "instr/bit"),
+            Throughput::Bytes(bytes)
+            | Throughput::BytesDecimal(bytes)
+            | Throughput::ElementsAndBytes { bytes, .. } => (bytes, "instr/B"),
+            Throughput::Elements(elements) => (elements, "instr/elem"),
+        };
+        for value in values {
+            *value /= per as f64;
+        }
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "instr"
+    }
+}
@@ -0,0 +1,155 @@
+use crate::foo::Foo;
+use crate::format::{self, Format};
+use crate::handwritten::{self, Handwritten};
+use criterion::measurement::Measurement;
+use criterion::{BenchmarkGroup, Criterion};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hint::black_box;
+
+#[derive(Serialize, Deserialize)]
+pub enum Enum {
+    Unit,
+    Newtype(u64),
+    Tuple(u64, u64),
+    Struct { a: u64, b: u64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnitStruct;
+
+pub trait Shape: Serialize + DeserializeOwned + Handwritten {}
+
+impl<T> Shape for T where T: Serialize + DeserializeOwned + Handwritten {}
+
+pub trait Visitor {
+    fn visit<T: Shape>(&mut self, name: &'static str, value: &T);
+}
+
+pub fn for_each(visitor: &mut impl Visitor) {
+    visitor.visit("foo", &Foo::default());
+    visitor.visit("unit_variant", &Enum::Unit);
+    visitor.visit("newtype_variant", &Enum::Newtype(1337));
+    visitor.visit("tuple_variant", &Enum::Tuple(1337, 7331));
+    visitor.visit("struct_variant", &Enum::Struct { a: 1337, b: 7331 });
+    visitor.visit("option_none", &None::<u64>);
+    visitor.visit("option_some", &Some(1337u64));
+    visitor.visit("char", &'\u{e9}');
+    visitor.visit("i128", &-1337i128);
+    visitor.visit("u128", &u128::MAX);
+    visitor.visit("f32", &1.337f32);
+    visitor.visit("f64", &1.337f64);
+    visitor.visit("tuple", &(1337u64, true, 1.337f64));
+    visitor.visit("array", &[1337u32; 32]);
+    visitor.visit("hashmap", &map::<HashMap<String, u64>>());
+    visitor.visit("btreemap", &map::<BTreeMap<String, u64>>());
+    visitor.visit("nested_vec", &vec![vec![1337u64; 8]; 8]);
+    visitor.visit("unit_struct", &UnitStruct);
+}
+
+fn map<M>() -> M
+where
+    M: FromIterator<(String, u64)>,
+{
+    (0..16).map(|i| (format!("key{i}"), i)).collect()
+}
+
+/// Receives every benchmark of one shape: its id within the shape's group and
+/// the routine to run once per iteration. The wall-clock and instruction-count
+/// harnesses both go through [`routines`], so they measure the same ids.
+pub trait Routines {
+    fn routine<O, R>(&mut self, id: &str, routine: R)
+    where
+        R: FnMut() -> O;
+}
+
+pub fn routines<T: Shape>(name: &str, value: &T, routines: &mut impl Routines) {
+    struct FormatVisitor<'a, R, T> {
+        routines: &'a mut R,
+        value: &'a T,
+    }
+
+    impl<'a, R: Routines, T: Shape> format::Visitor for FormatVisitor<'a, R, T> {
+        fn visit<F: Format>(&mut self) {
+            let value = self.value;
+
+            let mut bytes = Vec::with_capacity(1024);
+            self.routines
+                .routine(&format!("{}_serialize", F::NAME), || {
+                    let value = black_box(value);
+                    bytes.clear();
+                    F::serialize(&mut bytes, value);
+                });
+
+            let mut bytes = Vec::new();
+            F::serialize(&mut bytes, value);
+            self.routines
+                .routine(&format!("{}_deserialize", F::NAME), || {
+                    let bytes = black_box(&bytes);
+                    F::deserialize::<T>(bytes)
+                });
+        }
+    }
+
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, value).unwrap();
+    let mut handwritten = Vec::new();
+    value.encode(&mut handwritten);
+    assert_eq!(
+        handwritten, expected,
+        "handwritten encoding of {name} differs from serde_bench",
+    );
+
+    let mut bytes = Vec::with_capacity(1024);
+    routines.routine("handwritten_serialize", || {
+        let value = black_box(value);
+        bytes.clear();
+        value.encode(&mut bytes);
+    });
+
+    routines.routine("handwritten_deserialize", || {
+        let bytes = black_box(&expected);
+        handwritten::decode::<T>(bytes).unwrap()
+    });
+
+    let mut bytes = Vec::new();
+    routines.routine("serde_serialize_vec", || {
+        let value = black_box(value);
+        bytes.clear();
+        serde_bench::serialize_with_size_hint(&mut bytes, value, expected.len()).unwrap();
+    });
+
+    format::for_each(&mut FormatVisitor { routines, value });
+}
+
+pub struct Bench<'a, M: Measurement> {
+    c: &'a mut Criterion<M>,
+}
+
+impl<'a, M: Measurement> Bench<'a, M> {
+    pub fn new(c: &'a mut Criterion<M>) -> Self {
+        Bench { c }
+    }
+}
+
+impl<'a, M: Measurement> Visitor for Bench<'a, M> {
+    fn visit<T: Shape>(&mut self, name: &'static str, value: &T) {
+        struct Group<'a, 'b, M: Measurement> {
+            group: &'a mut BenchmarkGroup<'b, M>,
+        }
+
+        impl<'a, 'b, M: Measurement> Routines for Group<'a, 'b, M> {
+            fn routine<O, R>(&mut self, id: &str, mut routine: R)
+            where
+                R: FnMut() -> O,
+            {
+                self.group.bench_function(id, |b| b.iter(&mut routine));
+            }
+        }
+
+        let mut group = self.c.benchmark_group(name);
+        routines(name, value, &mut Group { group: &mut group });
+        group.finish();
+    }
+}
@@ -0,0 +1,50 @@
+//! Bulk decoding of many records written back to back into one buffer.
+
+use crate::foo::Foo;
+use criterion::{Criterion, Throughput};
+use serde_bench::StreamDeserializer;
+use std::hint::black_box;
+
+const RECORDS: usize = 1000;
+
+pub fn bench(c: &mut Criterion) {
+    let foo = Foo::default();
+    let mut bytes = Vec::new();
+    let mut framed = Vec::new();
+    for _ in 0..RECORDS {
+        serde_bench::serialize(&mut bytes, &foo).unwrap();
+        serde_bench::serialize_framed(&mut framed, &foo).unwrap();
+    }
+
+    let mut group = c.benchmark_group("stream");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    group.bench_function("serde_stream_slice", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            for foo in StreamDeserializer::<_, Foo>::from_slice(bytes) {
+                black_box(foo.unwrap());
+            }
+        });
+    });
+
+    group.bench_function("serde_stream_slice_framed", |b| {
+        b.iter(|| {
+            let framed = black_box(&framed);
+            for foo in StreamDeserializer::<_, Foo>::from_slice(framed).framed() {
+                black_box(foo.unwrap());
+            }
+        });
+    });
+
+    group.bench_function("serde_stream_reader", |b| {
+        b.iter(|| {
+            let bytes = black_box(bytes.as_slice());
+            for foo in StreamDeserializer::<_, Foo>::from_reader(bytes) {
+                black_box(foo.unwrap());
+            }
+        });
+    });
+
+    group.finish();
+}
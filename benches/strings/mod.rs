@@ -0,0 +1,43 @@
+//! String-heavy datasets, for comparing UTF-8 validation with `std` against
+//! the `simd` feature and against skipping it through `deserialize_trusted`.
+//! Run once with and once without `--features simd` to compare validators.
+
+use criterion::{Criterion, Throughput};
+use std::hint::black_box;
+
+const ASCII: &str = "The quick brown fox jumps over the lazy dog while the \
+    serializer writes every field of the struct in declaration order. ";
+
+const CJK: &str = "素早い茶色の狐がのろまな犬を飛び越える。\
+    序列化程序按照声明顺序写入结构体的每个字段。\
+    다람쥐 헌 쳇바퀴에 타고파. ";
+
+pub fn bench(c: &mut Criterion) {
+    dataset(c, "strings_ascii", ASCII);
+    dataset(c, "strings_cjk", CJK);
+}
+
+fn dataset(c: &mut Criterion, name: &str, text: &str) {
+    let strings: Vec<String> = (1..=256).map(|i| text.repeat(i % 8 + 1)).collect();
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &strings).unwrap();
+
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+
+    group.bench_function("serde_deserialize", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            serde_bench::deserialize::<Vec<&str>>(bytes).unwrap()
+        });
+    });
+
+    group.bench_function("serde_deserialize_trusted", |b| {
+        b.iter(|| {
+            let bytes = black_box(&bytes);
+            unsafe { serde_bench::deserialize_trusted::<Vec<&str>>(bytes) }.unwrap()
+        });
+    });
+
+    group.finish();
+}
@@ -0,0 +1,173 @@
+//! Just enough of the ELF format to find section sizes and function symbols,
+//! for both 32- and 64-bit objects of either byte order.
+
+const SHT_SYMTAB: u32 = 2;
+const STT_FUNC: u8 = 2;
+
+pub struct Elf<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    little_endian: bool,
+}
+
+pub struct Section<'a> {
+    pub name: &'a str,
+    pub size: u64,
+    kind: u32,
+    offset: u64,
+    link: u32,
+}
+
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub size: u64,
+}
+
+impl<'a> Elf<'a> {
+    /// Returns `None` if `data` is not an ELF file.
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < 16 || data[..4] != *b"\x7fELF" {
+            return None;
+        }
+        let is_64 = match data[4] {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+        let little_endian = match data[5] {
+            1 => true,
+            2 => false,
+            _ => return None,
+        };
+        Some(Elf {
+            data,
+            is_64,
+            little_endian,
+        })
+    }
+
+    pub fn sections(&self) -> Option<Vec<Section<'a>>> {
+        let (shoff, shentsize, shnum, shstrndx) = if self.is_64 {
+            (
+                self.u64(0x28)?,
+                self.u16(0x3a)?,
+                self.u16(0x3c)?,
+                self.u16(0x3e)?,
+            )
+        } else {
+            (
+                u64::from(self.u32(0x20)?),
+                self.u16(0x2e)?,
+                self.u16(0x30)?,
+                self.u16(0x32)?,
+            )
+        };
+
+        // Name offset, type, file offset, size and link of the section at
+        // `index`.
+        let header = |index: u16| -> Option<(u32, u32, u64, u64, u32)> {
+            let at = usize::try_from(shoff).ok()? + usize::from(index) * usize::from(shentsize);
+            let name = self.u32(at)?;
+            let kind = self.u32(at + 4)?;
+            if self.is_64 {
+                Some((
+                    name,
+                    kind,
+                    self.u64(at + 0x18)?,
+                    self.u64(at + 0x20)?,
+                    self.u32(at + 0x28)?,
+                ))
+            } else {
+                Some((
+                    name,
+                    kind,
+                    u64::from(self.u32(at + 0x10)?),
+                    u64::from(self.u32(at + 0x14)?),
+                    self.u32(at + 0x18)?,
+                ))
+            }
+        };
+
+        let (_, _, names_offset, _, _) = header(shstrndx)?;
+        let mut sections = Vec::with_capacity(usize::from(shnum));
+        for index in 0..shnum {
+            let (name, kind, offset, size, link) = header(index)?;
+            sections.push(Section {
+                name: self.str(names_offset + u64::from(name))?,
+                size,
+                kind,
+                offset,
+                link,
+            });
+        }
+        Some(sections)
+    }
+
+    /// Function symbols from the static symbol table, which is missing from
+    /// stripped binaries.
+    pub fn functions(&self) -> Option<Vec<Symbol<'a>>> {
+        let sections = self.sections()?;
+        let symtab = sections.iter().find(|section| section.kind == SHT_SYMTAB)?;
+        let strtab = sections.get(usize::try_from(symtab.link).ok()?)?;
+
+        let entsize = if self.is_64 { 24 } else { 16 };
+        let start = usize::try_from(symtab.offset).ok()?;
+        let count = usize::try_from(symtab.size).ok()? / entsize;
+        let mut functions = Vec::new();
+        for index in 0..count {
+            let at = start + index * entsize;
+            let name = self.u32(at)?;
+            let (info, size) = if self.is_64 {
+                (*self.data.get(at + 4)?, self.u64(at + 0x10)?)
+            } else {
+                (*self.data.get(at + 0xc)?, u64::from(self.u32(at + 8)?))
+            };
+            if info & 0xf != STT_FUNC || size == 0 {
+                continue;
+            }
+            functions.push(Symbol {
+                name: self.str(strtab.offset + u64::from(name))?,
+                size,
+            });
+        }
+        Some(functions)
+    }
+
+    fn bytes<const N: usize>(&self, at: usize) -> Option<[u8; N]> {
+        self.data.get(at..at.checked_add(N)?)?.try_into().ok()
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let bytes = self.bytes(at)?;
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let bytes = self.bytes(at)?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, at: usize) -> Option<u64> {
+        let bytes = self.bytes(at)?;
+        Some(if self.little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    // NUL-terminated string starting at file offset `at`.
+    fn str(&self, at: u64) -> Option<&'a str> {
+        let rest = self.data.get(usize::try_from(at).ok()?..)?;
+        let len = rest.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&rest[..len]).ok()
+    }
+}
@@ -0,0 +1,205 @@
+//! Sources of the generated crates: `count` synthetic types deriving
+//! Serialize and Deserialize that a main function round-trips through
+//! serde-bench, or the bench datasets with one entry point each for
+//! serializing and deserializing them.
+
+use std::fmt::Write as _;
+
+// The `embedded` profile optimizes for size the way firmware builds do.
+pub fn manifest(name: &str, serde_bench: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+serde = {{ version = "1", features = ["derive"] }}
+serde-bench = {{ path = {serde_bench:?} }}
+
+[profile.embedded]
+inherits = "release"
+opt-level = "s"
+codegen-units = 1
+panic = "abort"
+
+[workspace]
+"#,
+    )
+}
+
+// Two structs for every enum. Each type after the first contains the one
+// before it, so that deserializing it goes through nested derived impls the
+// way real message types do.
+pub fn main_rs(count: usize) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "\
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+",
+    );
+
+    for i in 0..count {
+        let prev = if i == 0 {
+            String::new()
+        } else {
+            format!("T{}", i - 1)
+        };
+        out.push_str("\n#[derive(Serialize, Deserialize, Default)]\n");
+        if i % 3 == 2 {
+            let _ = writeln!(out, "pub enum T{i} {{");
+            out.push_str("    #[default]\n    Unit,\n    Newtype(u32),\n");
+            out.push_str("    Tuple(String, Option<u64>),\n");
+            out.push_str("    Struct { a: Vec<u16>, b: bool");
+            if !prev.is_empty() {
+                let _ = write!(out, ", c: {prev}");
+            }
+            out.push_str(" },\n}\n");
+        } else {
+            let _ = writeln!(out, "pub struct T{i} {{");
+            out.push_str("    a: u8,\n    b: String,\n    c: Vec<u32>,\n");
+            out.push_str("    d: Option<i64>,\n    e: (f32, char),\n");
+            if !prev.is_empty() {
+                let _ = writeln!(out, "    f: {prev},");
+            }
+            out.push_str("}\n");
+        }
+    }
+
+    out.push_str(
+        "
+fn roundtrip<T>() -> usize
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &black_box(T::default())).unwrap();
+    let value: T = serde_bench::deserialize(black_box(&bytes)).unwrap();
+    black_box(value);
+    bytes.len()
+}
+
+fn main() {
+    let mut len = 0;
+",
+    );
+    for i in 0..count {
+        let _ = writeln!(out, "    len += roundtrip::<T{i}>();");
+    }
+    out.push_str("    println!(\"{len}\");\n}\n");
+    out
+}
+
+// Mirrors `shapes::for_each` in benches/shapes/mod.rs.
+const DATASETS: &[(&str, &str, &str)] = &[
+    ("foo", "Foo", "Foo::default()"),
+    ("unit_variant", "Enum", "Enum::Unit"),
+    ("newtype_variant", "Enum", "Enum::Newtype(1337)"),
+    ("tuple_variant", "Enum", "Enum::Tuple(1337, 7331)"),
+    (
+        "struct_variant",
+        "Enum",
+        "Enum::Struct { a: 1337, b: 7331 }",
+    ),
+    ("option_none", "Option<u64>", "None"),
+    ("option_some", "Option<u64>", "Some(1337)"),
+    ("char", "char", "'\\u{e9}'"),
+    ("i128", "i128", "-1337"),
+    ("u128", "u128", "u128::MAX"),
+    ("f32", "f32", "1.337"),
+    ("f64", "f64", "1.337"),
+    ("tuple", "(u64, bool, f64)", "(1337, true, 1.337)"),
+    ("array", "[u32; 32]", "[1337; 32]"),
+    ("hashmap", "HashMap<String, u64>", "map()"),
+    ("btreemap", "BTreeMap<String, u64>", "map()"),
+    ("nested_vec", "Vec<Vec<u64>>", "vec![vec![1337; 8]; 8]"),
+    ("unit_struct", "UnitStruct", "UnitStruct"),
+];
+
+pub fn dataset_names() -> impl Iterator<Item = &'static str> {
+    DATASETS.iter().map(|(name, _, _)| *name)
+}
+
+// Every dataset gets a `serialize_*` and `deserialize_*` function kept out of
+// line, so that their symbol sizes include whatever got inlined into them.
+pub fn datasets_rs() -> String {
+    let mut out = String::new();
+    out.push_str(
+        "\
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hint::black_box;
+
+#[derive(Serialize, Deserialize)]
+pub struct Foo {
+    bar: String,
+    baz: u64,
+    derp: bool,
+}
+
+impl Default for Foo {
+    fn default() -> Self {
+        Foo {
+            bar: \"hello\".into(),
+            baz: 1337,
+            derp: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Enum {
+    Unit,
+    Newtype(u64),
+    Tuple(u64, u64),
+    Struct { a: u64, b: u64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UnitStruct;
+
+fn map<M>() -> M
+where
+    M: FromIterator<(String, u64)>,
+{
+    (0..16).map(|i| (format!(\"key{i}\"), i)).collect()
+}
+",
+    );
+
+    for (name, ty, value) in DATASETS {
+        let _ = write!(
+            out,
+            "
+#[inline(never)]
+pub fn serialize_{name}(out: &mut Vec<u8>, value: &{ty}) {{
+    serde_bench::serialize(out, value).unwrap();
+}}
+
+#[inline(never)]
+pub fn deserialize_{name}(bytes: &[u8]) -> {ty} {{
+    serde_bench::deserialize(bytes).unwrap()
+}}
+
+fn run_{name}() {{
+    let value: {ty} = {value};
+    let mut bytes = Vec::new();
+    serialize_{name}(&mut bytes, black_box(&value));
+    black_box(deserialize_{name}(black_box(&bytes)));
+}}
+",
+        );
+    }
+
+    out.push_str("\nfn main() {\n");
+    for (name, _, _) in DATASETS {
+        let _ = writeln!(out, "    run_{name}();");
+    }
+    out.push_str("}\n");
+    out
+}
@@ -0,0 +1,273 @@
+//! Measures what Serde derives cost to compile against serde-bench. For each
+//! count N, generates a crate with N derived structs and enums that are all
+//! serialized and deserialized through serde-bench, builds it, and reports the
+//! time to compile that crate alone, the lines of LLVM IR it produced, and the
+//! size of the `.text` section of the resulting binary.
+//!
+//! ```console
+//! $ cargo run -p serde-bench-codegen -- --counts 10,100,200
+//! ```
+//!
+//! With `--symbols`, instead builds a binary that serializes and deserializes
+//! each bench dataset and reports the sizes of the functions in its symbol
+//! table: the out-of-line entry point for every dataset, and every function
+//! monomorphized from serde-bench. The `embedded` profile builds with
+//! `opt-level = "s"`, one codegen unit and `panic = "abort"`.
+//!
+//! ```console
+//! $ cargo run -p serde-bench-codegen -- --symbols --profile embedded
+//! ```
+//!
+//! The generated crates live in target/codegen and share one target
+//! directory, so dependencies are only built once and each measured build
+//! recompiles just the generated code.
+
+mod elf;
+mod generate;
+
+use crate::elf::Elf;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+usage: serde-bench-codegen [--counts N,N,...] [--profile NAME]
+       serde-bench-codegen --symbols [--filter TEXT] [--profile NAME]
+
+    --counts N,N,...   numbers of derived types to generate (default: 10,50,100,200)
+    --symbols          report per-function sizes for the bench datasets
+    --filter TEXT      functions to list besides the entry points (default: serde_bench)
+    --profile NAME     `release`, `dev` or `embedded` (default: release)";
+
+struct Args {
+    counts: Vec<usize>,
+    symbols: bool,
+    filter: String,
+    profile: String,
+}
+
+struct Measurement {
+    count: usize,
+    time: Duration,
+    ir_lines: Option<usize>,
+    text_size: Option<u64>,
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let target_dir = env::var_os("CARGO_TARGET_DIR").unwrap_or_else(|| OsString::from("target"));
+    // Absolute, because cargo runs in each generated crate's directory.
+    let work = env::current_dir().unwrap().join(target_dir).join("codegen");
+    if args.symbols {
+        report_symbols(&work, &args);
+        return;
+    }
+
+    let mut measurements = Vec::new();
+    for &count in &args.counts {
+        eprintln!("measuring {count} types");
+        measurements.push(measure(&work, count, &args.profile));
+    }
+
+    println!("| types | compile time | LLVM IR lines | .text bytes |");
+    println!("|--:|--:|--:|--:|");
+    for m in &measurements {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_owned());
+        println!(
+            "| {} | {:.2} s | {} | {} |",
+            m.count,
+            m.time.as_secs_f64(),
+            optional(m.ir_lines.map(|lines| lines.to_string())),
+            optional(m.text_size.map(|size| size.to_string())),
+        );
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        counts: vec![10, 50, 100, 200],
+        symbols: false,
+        filter: "serde_bench".to_owned(),
+        profile: "release".to_owned(),
+    };
+
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .ok_or_else(|| format!("missing value for {flag}"))
+        };
+        match arg.as_str() {
+            "--counts" => {
+                let counts = value("--counts")?;
+                args.counts = counts
+                    .split(',')
+                    .map(|count| match count.trim().parse() {
+                        Ok(count) if count > 0 => Ok(count),
+                        _ => Err(format!("invalid count: {count}")),
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--symbols" => args.symbols = true,
+            "--filter" => args.filter = value("--filter")?,
+            "--profile" => args.profile = value("--profile")?,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(args)
+}
+
+fn measure(work: &Path, count: usize, profile: &str) -> Measurement {
+    let name = format!("codegen-n{count}");
+    let main_rs = generate::main_rs(count);
+    let dir = create_crate(work, &name, &main_rs);
+
+    // Builds dependencies and the crate once, then rewrites main.rs so that
+    // the timed build compiles only the generated code.
+    let shared_target = work.join("target");
+    let emit = ["--emit", "llvm-ir,link"];
+    build(&dir, &shared_target, profile, &emit);
+    fs::write(dir.join("src").join("main.rs"), &main_rs).unwrap();
+    let start = Instant::now();
+    build(&dir, &shared_target, profile, &emit);
+    let time = start.elapsed();
+
+    let profile_dir = profile_dir(&shared_target, profile);
+    let ir_lines = newest_ir(&profile_dir.join("deps"), &name.replace('-', "_"))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|ir| ir.lines().count());
+    let text_size = fs::read(profile_dir.join(&name)).ok().and_then(|binary| {
+        let elf = Elf::parse(&binary)?;
+        let sections = elf.sections()?;
+        let text = sections.iter().find(|section| section.name == ".text")?;
+        Some(text.size)
+    });
+
+    Measurement {
+        count,
+        time,
+        ir_lines,
+        text_size,
+    }
+}
+
+fn report_symbols(work: &Path, args: &Args) {
+    let name = "codegen-symbols";
+    let dir = create_crate(work, name, &generate::datasets_rs());
+    let shared_target = work.join("target");
+    // v0 mangling keeps the generic arguments of each monomorphization.
+    build(
+        &dir,
+        &shared_target,
+        &args.profile,
+        &["-C", "symbol-mangling-version=v0"],
+    );
+
+    let path = profile_dir(&shared_target, &args.profile).join(name);
+    let binary = fs::read(&path).unwrap();
+    let Some(functions) = Elf::parse(&binary).and_then(|elf| elf.functions()) else {
+        eprintln!("error: no ELF symbol table in {}", path.display());
+        process::exit(2);
+    };
+    let mut sizes = BTreeMap::new();
+    for function in &functions {
+        let name = format!("{:#}", rustc_demangle::demangle(function.name));
+        *sizes.entry(name).or_insert(0) += function.size;
+    }
+
+    let size = |name: String| match sizes.get(&format!("codegen_symbols::{name}")) {
+        Some(size) => size.to_string(),
+        None => "inlined".to_owned(),
+    };
+    println!("| dataset | serialize | deserialize |");
+    println!("|:--|--:|--:|");
+    for dataset in generate::dataset_names() {
+        println!(
+            "| {} | {} | {} |",
+            dataset,
+            size(format!("serialize_{dataset}")),
+            size(format!("deserialize_{dataset}")),
+        );
+    }
+
+    let mut matching: Vec<(&String, &u64)> = sizes
+        .iter()
+        .filter(|(name, _)| name.contains(&args.filter))
+        .collect();
+    matching.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let total: u64 = matching.iter().map(|(_, size)| **size).sum();
+    println!();
+    println!("| bytes | function containing `{}` |", args.filter);
+    println!("|--:|:--|");
+    for (name, size) in matching {
+        println!("| {size} | `{name}` |");
+    }
+    println!("| {total} | total |");
+}
+
+// Writes the manifest and main.rs of a generated crate.
+fn create_crate(work: &Path, name: &str, main_rs: &str) -> PathBuf {
+    let dir = work.join(name);
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+
+    let serde_bench = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let manifest = generate::manifest(name, &serde_bench.to_string_lossy());
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    // Resolve to the same dependency versions as the workspace.
+    let lockfile = serde_bench.join("Cargo.lock");
+    if lockfile.exists() {
+        fs::copy(lockfile, dir.join("Cargo.lock")).unwrap();
+    }
+    fs::write(src.join("main.rs"), main_rs).unwrap();
+    dir
+}
+
+fn profile_dir(target_dir: &Path, profile: &str) -> PathBuf {
+    target_dir.join(if profile == "dev" { "debug" } else { profile })
+}
+
+fn build(dir: &Path, target_dir: &Path, profile: &str, rustc_args: &[&str]) {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let status = Command::new(cargo)
+        .current_dir(dir)
+        .env("CARGO_TARGET_DIR", target_dir)
+        .args(["rustc", "--quiet", "--profile", profile, "--"])
+        .args(rustc_args)
+        .status()
+        .expect("failed to run cargo rustc");
+    if !status.success() {
+        process::exit(status.code().unwrap_or(2));
+    }
+}
+
+// The .ll file that the last build emitted for the crate named `stem`.
+fn newest_ir(deps: &Path, stem: &str) -> Option<PathBuf> {
+    let prefix = format!("{stem}-");
+    fs::read_dir(deps)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(".ll")
+        })
+        .max_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok())
+        .map(|entry| entry.path())
+}
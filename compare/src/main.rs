@@ -0,0 +1,313 @@
+//! Runs the benchmarks, saves the results as a Criterion baseline named after
+//! the current git revision, and optionally compares them against a baseline
+//! saved by an earlier run. Exits with status 1 if any benchmark got slower
+//! by more than the tolerance.
+//!
+//! ```console
+//! $ cargo +nightly run -p serde-bench-compare -- --baseline 77d6d1e --tolerance 3
+//! ```
+//!
+//! With `--overhead`, also prints how much slower each dataset is through
+//! serde-bench than through its handwritten encoder and decoder.
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+const USAGE: &str = "\
+usage: serde-bench-compare [--bench NAME] [--baseline REV] [--tolerance PERCENT] [--overhead] [--no-run] [FILTER]
+
+    --bench NAME          bench target to run, `bench` or `instructions` (default: bench)
+    --baseline REV        revision of a previously saved run to compare against
+    --tolerance PERCENT   slowdown allowed before failing (default: 5)
+    --overhead            report serde-bench time relative to the handwritten baseline
+    --no-run              compare results already saved for the current revision
+    FILTER                only run benchmarks whose id contains FILTER";
+
+// Benchmark function names tracked by the regression gate.
+const PREFIXES: &[&str] = &["serde_", "bincode_", "postcard"];
+
+struct Args {
+    bench: String,
+    baseline: Option<String>,
+    tolerance: f64,
+    overhead: bool,
+    run: bool,
+    filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Benchmark {
+    full_id: String,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    median: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {msg}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let revision = git_revision();
+    if args.run {
+        run_bench(&args, &revision);
+    }
+
+    let dir = criterion_dir(&args.bench);
+    let current = load(&dir, &revision);
+    if current.is_empty() {
+        eprintln!(
+            "error: no results saved for {} in {}",
+            revision,
+            dir.display(),
+        );
+        process::exit(2);
+    }
+
+    if args.overhead {
+        print_overhead(&current, &args);
+    }
+
+    let Some(baseline_name) = &args.baseline else {
+        eprintln!("saved {} results as baseline {revision}", current.len());
+        return;
+    };
+    let baseline = load(&dir, baseline_name);
+    if baseline.is_empty() {
+        eprintln!(
+            "error: no results saved for {} in {}",
+            baseline_name,
+            dir.display(),
+        );
+        process::exit(2);
+    }
+
+    let unit = if args.bench == "instructions" {
+        "instr"
+    } else {
+        "ns"
+    };
+    let regressions = print_table(&baseline, &current, baseline_name, &revision, &args, unit);
+    if regressions > 0 {
+        eprintln!(
+            "{} benchmark{} regressed by more than {}%",
+            regressions,
+            if regressions == 1 { "" } else { "s" },
+            args.tolerance,
+        );
+        process::exit(1);
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        bench: "bench".to_owned(),
+        baseline: None,
+        tolerance: 5.0,
+        overhead: false,
+        run: true,
+        filter: None,
+    };
+
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .ok_or_else(|| format!("missing value for {flag}"))
+        };
+        match arg.as_str() {
+            "--bench" => args.bench = value("--bench")?,
+            "--baseline" => args.baseline = Some(value("--baseline")?),
+            "--tolerance" => {
+                let tolerance = value("--tolerance")?;
+                args.tolerance = tolerance
+                    .trim_end_matches('%')
+                    .parse()
+                    .map_err(|_| format!("invalid tolerance: {tolerance}"))?;
+            }
+            "--overhead" => args.overhead = true,
+            "--no-run" => args.run = false,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unrecognized option: {arg}")),
+            _ if args.filter.is_none() => args.filter = Some(arg),
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(args)
+}
+
+fn git_revision() -> String {
+    let output = Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .expect("failed to run git");
+    if !output.status.success() {
+        eprintln!("error: failed to determine git revision");
+        process::exit(2);
+    }
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
+fn run_bench(args: &Args, revision: &str) {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut cmd = Command::new(cargo);
+    cmd.args(["bench", "--bench", &args.bench, "--"])
+        .args(["--save-baseline", revision]);
+    if let Some(filter) = &args.filter {
+        cmd.arg(filter);
+    }
+    let status = cmd.status().expect("failed to run cargo bench");
+    if !status.success() {
+        process::exit(status.code().unwrap_or(2));
+    }
+}
+
+fn criterion_dir(bench: &str) -> PathBuf {
+    // Matches where the bench targets write their results; see
+    // benches/instructions.rs.
+    let target_dir = env::var_os("CARGO_TARGET_DIR").unwrap_or_else(|| OsString::from("target"));
+    let name = if bench == "instructions" {
+        "criterion-instructions"
+    } else {
+        "criterion"
+    };
+    PathBuf::from(target_dir).join(name)
+}
+
+fn load(dir: &Path, baseline: &str) -> BTreeMap<String, f64> {
+    let mut results = BTreeMap::new();
+    visit(dir, baseline, &mut results);
+    results
+}
+
+fn visit(dir: &Path, baseline: &str, results: &mut BTreeMap<String, f64>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if entry.file_name() != *baseline {
+            visit(&path, baseline, results);
+            continue;
+        }
+        let (Ok(benchmark), Ok(estimates)) = (
+            fs::read(path.join("benchmark.json")),
+            fs::read(path.join("estimates.json")),
+        ) else {
+            continue;
+        };
+        let benchmark: Benchmark = serde_json::from_slice(&benchmark).unwrap();
+        let estimates: Estimates = serde_json::from_slice(&estimates).unwrap();
+        results.insert(benchmark.full_id, estimates.median.point_estimate);
+    }
+}
+
+fn print_table(
+    baseline: &BTreeMap<String, f64>,
+    current: &BTreeMap<String, f64>,
+    baseline_name: &str,
+    revision: &str,
+    args: &Args,
+    unit: &str,
+) -> usize {
+    let ids: BTreeSet<&String> = baseline
+        .keys()
+        .chain(current.keys())
+        .filter(|id| is_tracked(id))
+        .collect();
+    let mut regressions = 0;
+
+    println!("| benchmark | {baseline_name} | {revision} | delta |");
+    println!("|:--|--:|--:|--:|");
+    for id in ids {
+        if let Some(filter) = &args.filter {
+            if !id.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let format = |value: Option<&f64>| match value {
+            Some(value) => format!("{value:.1} {unit}"),
+            None => "-".to_owned(),
+        };
+        let delta = match (baseline.get(id), current.get(id)) {
+            (Some(before), Some(after)) => {
+                let percent = (after - before) / before * 100.0;
+                if percent > args.tolerance {
+                    regressions += 1;
+                    format!("**{percent:+.1}%**")
+                } else {
+                    format!("{percent:+.1}%")
+                }
+            }
+            _ => "-".to_owned(),
+        };
+        println!(
+            "| {} | {} | {} | {} |",
+            id,
+            format(baseline.get(id)),
+            format(current.get(id)),
+            delta,
+        );
+    }
+
+    regressions
+}
+
+fn is_tracked(id: &str) -> bool {
+    let function = id.rsplit('/').next().unwrap();
+    PREFIXES.iter().any(|prefix| function.starts_with(prefix))
+}
+
+fn print_overhead(current: &BTreeMap<String, f64>, args: &Args) {
+    println!("Serde overhead (serde-bench time / handwritten time)\n");
+    println!("| dataset | serialize | deserialize |");
+    println!("|:--|--:|--:|");
+    for id in current.keys() {
+        let Some(dataset) = id.strip_suffix("/handwritten_serialize") else {
+            continue;
+        };
+        if let Some(filter) = &args.filter {
+            if !id.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let ratio = |op: &str| {
+            let serde = current.get(&format!("{dataset}/serde_{op}"));
+            let handwritten = current.get(&format!("{dataset}/handwritten_{op}"));
+            match (serde, handwritten) {
+                (Some(serde), Some(handwritten)) => format!("{:.2}x", serde / handwritten),
+                _ => "-".to_owned(),
+            }
+        };
+        println!(
+            "| {} | {} | {} |",
+            dataset,
+            ratio("serialize"),
+            ratio("deserialize"),
+        );
+    }
+    println!();
+}
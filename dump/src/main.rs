@@ -0,0 +1,481 @@
+//! Prints an annotated view of serde-bench bytes: the offset, bytes, field
+//! name and decoded value of everything in them.
+//!
+//! The binary has no access to the Rust type that was serialized, so the
+//! layout is described on the command line instead.
+//!
+//! ```console
+//! $ serde-bench-dump --hex '{id: u32, name: str, tags: Vec<u8>}' <<< '05000000 0200000000000000 6869 0000000000000000'
+//! 00000000                              struct
+//! 00000000  05 00 00 00                   id: u32 = 5
+//! 00000004  02 00 00 00 00 00 00 00 ..    name: str, len 2 = "hi"
+//! 0000000e  00 00 00 00 00 00 00 00       tags: seq, len 0
+//! ```
+
+use serde::de::{
+    DeserializeSeed, Deserializer, EnumAccess, Error as _, IgnoredAny, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read as _};
+use std::process;
+
+const USAGE: &str = "\
+usage: serde-bench-dump [--hex] TYPE [FILE]
+
+    --hex   input is hex text rather than raw bytes; whitespace is ignored
+    TYPE    layout of the input, for example `Vec<(u64, Option<str>)>`
+    FILE    file to read (default: stdin)
+
+types:
+    bool u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 char str bytes ()
+    Option<T>  Vec<T>  Map<K, V>  (T, U, ..)  [T; N]
+    {field: T, ..}                   struct
+    enum {A, B(T, ..), C {x: T, ..}}  enum";
+
+#[derive(Clone)]
+enum Type {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+    Str,
+    Bytes,
+    Unit,
+    Option(Box<Type>),
+    Seq(Box<Type>),
+    Map(Box<Type>, Box<Type>),
+    Tuple(Vec<Type>),
+    Struct(Fields),
+    Enum(&'static [&'static str], Vec<Variant>),
+}
+
+// Field and variant names are leaked because the Deserializer API wants
+// them as `&'static [&'static str]`.
+#[derive(Clone)]
+struct Fields {
+    names: &'static [&'static str],
+    types: Vec<Type>,
+}
+
+#[derive(Clone)]
+enum Variant {
+    Unit,
+    Tuple(Vec<Type>),
+    Struct(Fields),
+}
+
+fn main() {
+    let mut hex = false;
+    let mut positional = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--hex" => hex = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            _ if arg.starts_with('-') => usage_error(&format!("unrecognized option: {arg}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let (ty, path) = match positional.as_slice() {
+        [ty] => (ty, None),
+        [ty, path] => (ty, Some(path)),
+        [] => usage_error("missing TYPE"),
+        [_, _, arg, ..] => usage_error(&format!("unexpected argument: {arg}")),
+    };
+    let ty = match Parser::new(ty).parse() {
+        Ok(ty) => ty,
+        Err(msg) => usage_error(&format!("invalid type: {msg}")),
+    };
+
+    let input = if let Some(path) = path {
+        fs::read(path)
+    } else {
+        let mut input = Vec::new();
+        io::stdin().read_to_end(&mut input).map(|_| input)
+    };
+    let mut bytes = match input {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    };
+    if hex {
+        bytes = match decode_hex(&bytes) {
+            Ok(bytes) => bytes,
+            Err(msg) => {
+                eprintln!("error: {msg}");
+                process::exit(1);
+            }
+        };
+    }
+
+    print!("{}", serde_bench::dump_seed(&bytes, &ty));
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("error: {msg}\n\n{USAGE}");
+    process::exit(2);
+}
+
+fn decode_hex(text: &[u8]) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = text
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err("odd number of hex digits".to_owned());
+    }
+    pairs
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).map_err(|_| "invalid hex digit".to_owned())?;
+            u8::from_str_radix(pair, 16).map_err(|_| format!("invalid hex digit in {pair:?}"))
+        })
+        .collect()
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { rest: input }
+    }
+
+    fn parse(mut self) -> Result<Type, String> {
+        let ty = self.ty()?;
+        if !self.rest.trim_start().is_empty() {
+            return Err(format!("unexpected {:?}", self.rest.trim_start()));
+        }
+        Ok(ty)
+    }
+
+    fn ty(&mut self) -> Result<Type, String> {
+        if self.eat("(") {
+            let types = self.list(")", Self::ty)?;
+            return Ok(if types.is_empty() {
+                Type::Unit
+            } else {
+                Type::Tuple(types)
+            });
+        }
+        if self.eat("[") {
+            let element = self.ty()?;
+            self.expect(";")?;
+            let len = self.ident()?;
+            let len: usize = len
+                .parse()
+                .map_err(|_| format!("invalid array length {len:?}"))?;
+            self.expect("]")?;
+            return Ok(Type::Tuple((0..len).map(|_| element.clone()).collect()));
+        }
+        if self.eat("{") {
+            return Ok(Type::Struct(self.fields()?));
+        }
+
+        let name = self.ident()?;
+        Ok(match name {
+            "bool" => Type::Bool,
+            "i8" => Type::I8,
+            "i16" => Type::I16,
+            "i32" => Type::I32,
+            "i64" => Type::I64,
+            "i128" => Type::I128,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" | "usize" => Type::U64,
+            "u128" => Type::U128,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "char" => Type::Char,
+            "str" | "String" => Type::Str,
+            "bytes" => Type::Bytes,
+            "Option" => Type::Option(Box::new(self.generic()?)),
+            "Vec" => Type::Seq(Box::new(self.generic()?)),
+            "Map" => {
+                self.expect("<")?;
+                let key = self.ty()?;
+                self.expect(",")?;
+                let value = self.ty()?;
+                self.expect(">")?;
+                Type::Map(Box::new(key), Box::new(value))
+            }
+            "enum" => {
+                self.expect("{")?;
+                let variants = self.list("}", Self::variant)?;
+                if variants.is_empty() {
+                    return Err("enum without variants".to_owned());
+                }
+                let (names, variants): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+                Type::Enum(leak(names), variants)
+            }
+            _ => return Err(format!("unknown type {name:?}")),
+        })
+    }
+
+    fn generic(&mut self) -> Result<Type, String> {
+        self.expect("<")?;
+        let ty = self.ty()?;
+        self.expect(">")?;
+        Ok(ty)
+    }
+
+    // After the opening brace.
+    fn fields(&mut self) -> Result<Fields, String> {
+        let fields = self.list("}", |parser| {
+            let name = parser.ident()?.to_owned();
+            parser.expect(":")?;
+            Ok((name, parser.ty()?))
+        })?;
+        let (names, types): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        Ok(Fields {
+            names: leak(names),
+            types,
+        })
+    }
+
+    fn variant(&mut self) -> Result<(String, Variant), String> {
+        let name = self.ident()?.to_owned();
+        let variant = if self.eat("(") {
+            Variant::Tuple(self.list(")", Self::ty)?)
+        } else if self.eat("{") {
+            Variant::Struct(self.fields()?)
+        } else {
+            Variant::Unit
+        };
+        Ok((name, variant))
+    }
+
+    // Comma-separated items up to and including `close`, with an optional
+    // trailing comma.
+    fn list<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn ident(&mut self) -> Result<&'a str, String> {
+        self.rest = self.rest.trim_start();
+        let len = self
+            .rest
+            .find(|ch: char| !ch.is_alphanumeric() && ch != '_')
+            .unwrap_or(self.rest.len());
+        if len == 0 {
+            return Err(self.unexpected());
+        }
+        let (ident, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Ok(ident)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(format!("expected `{token}`, {}", self.unexpected()))
+        }
+    }
+
+    fn unexpected(&self) -> String {
+        match self.rest.chars().next() {
+            Some(ch) => format!("found {ch:?}"),
+            None => "found end of input".to_owned(),
+        }
+    }
+}
+
+fn leak(names: Vec<String>) -> &'static [&'static str] {
+    let names: Vec<&'static str> = names
+        .into_iter()
+        .map(|name| &*Box::leak(name.into_boxed_str()))
+        .collect();
+    Box::leak(names.into_boxed_slice())
+}
+
+// Drives the deserializer through the layout described by a `Type`. The
+// values themselves are discarded; `dump_seed` records them on the way.
+impl<'de> DeserializeSeed<'de> for &Type {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self {
+            Type::Bool => deserializer.deserialize_bool(IgnoredAny)?,
+            Type::I8 => deserializer.deserialize_i8(IgnoredAny)?,
+            Type::I16 => deserializer.deserialize_i16(IgnoredAny)?,
+            Type::I32 => deserializer.deserialize_i32(IgnoredAny)?,
+            Type::I64 => deserializer.deserialize_i64(IgnoredAny)?,
+            Type::I128 => deserializer.deserialize_i128(IgnoredAny)?,
+            Type::U8 => deserializer.deserialize_u8(IgnoredAny)?,
+            Type::U16 => deserializer.deserialize_u16(IgnoredAny)?,
+            Type::U32 => deserializer.deserialize_u32(IgnoredAny)?,
+            Type::U64 => deserializer.deserialize_u64(IgnoredAny)?,
+            Type::U128 => deserializer.deserialize_u128(IgnoredAny)?,
+            Type::F32 => deserializer.deserialize_f32(IgnoredAny)?,
+            Type::F64 => deserializer.deserialize_f64(IgnoredAny)?,
+            Type::Char => deserializer.deserialize_char(IgnoredAny)?,
+            Type::Str => deserializer.deserialize_str(IgnoredAny)?,
+            Type::Bytes => deserializer.deserialize_bytes(IgnoredAny)?,
+            Type::Unit => deserializer.deserialize_unit(IgnoredAny)?,
+            Type::Option(_) => return deserializer.deserialize_option(Walk(self)),
+            Type::Seq(_) => return deserializer.deserialize_seq(Walk(self)),
+            Type::Map(..) => return deserializer.deserialize_map(Walk(self)),
+            Type::Tuple(types) => return deserializer.deserialize_tuple(types.len(), Walk(self)),
+            Type::Struct(fields) => {
+                return deserializer.deserialize_struct("struct", fields.names, Walk(self));
+            }
+            Type::Enum(names, _) => {
+                return deserializer.deserialize_enum("enum", names, Walk(self))
+            }
+        };
+        Ok(())
+    }
+}
+
+struct Walk<'a>(&'a Type);
+
+impl<'de> Visitor<'de> for Walk<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value matching the TYPE argument")
+    }
+
+    fn visit_none<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Type::Option(ty) = self.0 else {
+            unreachable!()
+        };
+        ty.as_ref().deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        match self.0 {
+            Type::Seq(ty) => while seq.next_element_seed(ty.as_ref())?.is_some() {},
+            Type::Tuple(types) | Type::Struct(Fields { types, .. }) => {
+                for ty in types {
+                    if seq.next_element_seed(ty)?.is_none() {
+                        return Err(A::Error::custom("too few elements"));
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<(), A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Type::Map(key, value) = self.0 else {
+            unreachable!()
+        };
+        while map.next_entry_seed(key.as_ref(), value.as_ref())?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<(), A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let Type::Enum(_, variants) = self.0 else {
+            unreachable!()
+        };
+        let (index, variant) = data.variant_seed(VariantIndex)?;
+        let Some(ty) = variants.get(index as usize) else {
+            return Err(A::Error::custom(format_args!(
+                "variant index {index} out of range for {} variants",
+                variants.len(),
+            )));
+        };
+        match ty {
+            Variant::Unit => variant.unit_variant(),
+            Variant::Tuple(types) => {
+                let ty = Type::Tuple(types.clone());
+                variant.tuple_variant(types.len(), Walk(&ty))
+            }
+            Variant::Struct(fields) => {
+                let ty = Type::Struct(fields.clone());
+                variant.struct_variant(fields.names, Walk(&ty))
+            }
+        }
+    }
+}
+
+struct VariantIndex;
+
+impl<'de> DeserializeSeed<'de> for VariantIndex {
+    type Value = u32;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<u32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_u32(self)
+    }
+}
+
+impl Visitor<'_> for VariantIndex {
+    type Value = u32;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("variant index")
+    }
+
+    fn visit_u32<E>(self, index: u32) -> Result<u32, E> {
+        Ok(index)
+    }
+}
@@ -0,0 +1,66 @@
+//! Writes the benchmark datasets, encoded with serde-bench, to corpus/de as
+//! seeds for the `de` fuzz target.
+//!
+//! ```console
+//! $ cargo run --manifest-path fuzz/Cargo.toml --example corpus
+//! ```
+
+use serde::Serialize;
+use serde_bench_fuzz::{Bulk, Enum, Foo, UnitStruct};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus/de");
+    fs::create_dir_all(&dir).unwrap();
+
+    let write = |name: &str, bytes: Vec<u8>| fs::write(dir.join(name), bytes).unwrap();
+    let map = |i| (format!("key{i}"), i);
+
+    write(
+        "foo",
+        encode(&Foo {
+            bar: "hello".to_owned(),
+            baz: 1337,
+            derp: true,
+        }),
+    );
+    write("unit_variant", encode(&Enum::Unit));
+    write("newtype_variant", encode(&Enum::Newtype(1337)));
+    write("tuple_variant", encode(&Enum::Tuple(1337, 7331)));
+    write("struct_variant", encode(&Enum::Struct { a: 1337, b: 7331 }));
+    write("option_none", encode(&None::<u64>));
+    write("option_some", encode(&Some(1337u64)));
+    write("char", encode(&'\u{e9}'));
+    write("i128", encode(&-1337i128));
+    write("u128", encode(&u128::MAX));
+    write("f32", encode(&1.337f32));
+    write("f64", encode(&1.337f64));
+    write("tuple", encode(&(1337u64, true, 1.337f64)));
+    write("array", encode(&[1337u32; 32]));
+    write(
+        "hashmap",
+        encode(&(0..16).map(map).collect::<HashMap<_, u64>>()),
+    );
+    write(
+        "btreemap",
+        encode(&(0..16).map(map).collect::<BTreeMap<_, u64>>()),
+    );
+    write("nested_vec", encode(&vec![vec![1337u64; 8]; 8]));
+    write("unit_struct", encode(&UnitStruct));
+    write(
+        "bulk",
+        encode(&Bulk {
+            ints: vec![1337; 8],
+            floats: vec![1.337; 8],
+            array: [1337; 8],
+        }),
+    );
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, value).unwrap();
+    bytes
+}
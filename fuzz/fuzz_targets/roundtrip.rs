@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_bench_fuzz::Value;
+
+fuzz_target!(|value: Value| {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &value).unwrap();
+
+    let decoded: Value = serde_bench::deserialize(&bytes).unwrap();
+
+    // Compare encodings rather than values so that NaN round trips count.
+    let mut again = Vec::new();
+    serde_bench::serialize(&mut again, &decoded).unwrap();
+    assert_eq!(bytes, again);
+});
@@ -0,0 +1,130 @@
+#![allow(clippy::disallowed_names)]
+
+use arbitrary::Arbitrary;
+use serde::{Deserialize, Serialize};
+use serde_bench::{Config, Width};
+use std::collections::{BTreeMap, HashMap};
+
+// The same datasets as the benchmarks in benches/.
+
+#[derive(Serialize, Deserialize, Arbitrary, Debug)]
+pub struct Foo {
+    pub bar: String,
+    pub baz: u64,
+    pub derp: bool,
+}
+
+#[derive(Serialize, Deserialize, Arbitrary, Debug)]
+pub enum Enum {
+    Unit,
+    Newtype(u64),
+    Tuple(u64, u64),
+    Struct { a: u64, b: u64 },
+}
+
+#[derive(Serialize, Deserialize, Arbitrary, Debug)]
+pub struct UnitStruct;
+
+#[derive(Serialize, Deserialize, Arbitrary, Debug)]
+pub struct Bulk {
+    #[serde(with = "serde_bench::bulk")]
+    pub ints: Vec<u32>,
+    #[serde(with = "serde_bench::bulk")]
+    pub floats: Vec<f64>,
+    #[serde(with = "serde_bench::bulk::array")]
+    pub array: [u16; 8],
+}
+
+#[derive(Serialize, Deserialize, Arbitrary, Debug)]
+pub enum Value {
+    Foo(Foo),
+    Enum(Enum),
+    Option(Option<Box<Value>>),
+    Char(char),
+    I128(i128),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Tuple((u64, bool, f64)),
+    Array([u32; 8]),
+    BTreeMap(BTreeMap<String, Value>),
+    Seq(Vec<Value>),
+    NestedVec(Vec<Vec<u64>>),
+    UnitStruct(UnitStruct),
+    Bytes(#[serde(with = "serde_bytes_compat")] Vec<u8>),
+    Bulk(Bulk),
+}
+
+// Routes Vec<u8> through serialize_bytes / deserialize_byte_buf.
+mod serde_bytes_compat {
+    use serde::de::{Deserializer, Error, Visitor};
+    use serde::Serializer;
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Decodes `bytes` as each of the benchmarked types, with the default config
+/// and with narrow and varint lengths and variant indices. Errors are
+/// expected; panics are bugs.
+pub fn decode_all(bytes: &[u8]) {
+    let configs = [
+        Config::new()
+            .length(Width::Varint)
+            .variant_index(Width::Varint),
+        Config::new().length(Width::U8).variant_index(Width::U8),
+    ];
+
+    macro_rules! decode {
+        ($($ty:ty),* $(,)?) => {
+            $(
+                let _ = serde_bench::deserialize::<$ty>(bytes);
+                for config in configs {
+                    let _ = serde_bench::deserialize_with_config::<$ty>(bytes, config);
+                }
+            )*
+        };
+    }
+
+    decode! {
+        Foo,
+        Enum,
+        Option<u64>,
+        char,
+        i128,
+        u128,
+        f32,
+        f64,
+        (u64, bool, f64),
+        [u32; 32],
+        HashMap<String, u64>,
+        BTreeMap<String, u64>,
+        Vec<Vec<u64>>,
+        UnitStruct,
+        Bulk,
+        &str,
+        &[u8],
+        Vec<char>,
+        Value,
+    }
+}
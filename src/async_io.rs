@@ -0,0 +1,81 @@
+//! Frames over tokio's `AsyncRead` and `AsyncWrite`.
+//!
+//! The format itself stays synchronous: a message is serialized into a buffer
+//! and written as one frame, or a whole frame is read into a buffer and then
+//! deserialized. Frames are the same as those of a default [`FrameConfig`],
+//! a native-endian u64 length followed by the message, so either side can be
+//! a [`FrameWriter`] or [`FrameReader`] instead. Messages use the default
+//! [`Config`].
+//!
+//! [`Config`]: crate::Config
+//! [`FrameConfig`]: crate::FrameConfig
+//! [`FrameWriter`]: crate::FrameWriter
+//! [`FrameReader`]: crate::FrameReader
+
+use crate::de::Deserializer;
+use crate::read::{Read as _, SliceRead};
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Serializes `value` and writes it to `writer` as one frame. Nothing is
+/// written if serialization fails.
+///
+/// The writer is not flushed, so that a batch of frames can be written before
+/// flushing once.
+pub async fn serialize_async<W, T>(writer: &mut W, value: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+    T: ?Sized + Serialize,
+{
+    let mut buf = Vec::new();
+    crate::serialize_framed(&mut buf, &value)?;
+    writer.write_all(&buf).await?;
+    Ok(())
+}
+
+/// Reads the next frame from `reader` and deserializes it, or returns `None`
+/// if the reader is at end of input where a frame would start.
+pub async fn deserialize_async<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    T: DeserializeOwned,
+{
+    let Some(len) = read_len(reader).await? else {
+        return Ok(None);
+    };
+
+    // Grows with the bytes actually received rather than trusting `len`.
+    let mut buf = Vec::new();
+    reader.take(len).read_to_end(&mut buf).await?;
+    if (buf.len() as u64) < len {
+        return Err(Error::new("unexpected end of input"));
+    }
+
+    let mut de = Deserializer::new(SliceRead::new(&buf));
+    let value = T::deserialize(&mut de)?;
+    if !de.read.at_end()? {
+        return Err(Error::new("trailing bytes in frame"));
+    }
+    Ok(Some(value))
+}
+
+// Distinguishes a clean end of input before the prefix from one inside it.
+async fn read_len<R>(reader: &mut R) -> Result<Option<u64>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut buf = [0; 8];
+    loop {
+        match reader.read(&mut buf[..1]).await {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    reader.read_exact(&mut buf[1..]).await?;
+    Ok(Some(u64::from_ne_bytes(buf)))
+}
@@ -0,0 +1,514 @@
+//! Bulk encoding of primitive slices and arrays, for use with
+//! `#[serde(with = "...")]`.
+//!
+//! Serde serializes a `Vec<f64>` one element at a time, which in serde-bench
+//! is one 8-byte write per element. These functions write the whole slice
+//! with a single `write_all` and read it back with a single bulk read. The
+//! bytes are identical to the element-by-element encoding, so data written
+//! either way can be read either way.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Samples {
+//!     #[serde(with = "serde_bench::bulk")]
+//!     values: Vec<f64>,
+//!     #[serde(with = "serde_bench::bulk::array")]
+//!     histogram: [u32; 16],
+//! }
+//! ```
+//!
+//! For `Vec<u8>` the `serde_bytes` crate does the same job and produces the
+//! same bytes.
+//!
+//! Other formats see the elements as a byte string of their native-endian
+//! representation.
+
+use crate::read::{Read, Reference};
+use crate::{Error, Result};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Impossible, Serialize, SerializeTuple, Serializer};
+use std::borrow::Cow;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::{fmt, mem, ptr, slice};
+
+// Newtype name that tells the serde-bench Serializer and Deserializer to
+// write or read the wrapped bytes without a length prefix.
+pub(crate) const RAW: &str = "$serde_bench::private::Raw";
+
+// Newtype name that tells the serde-bench Serializer and Deserializer that the
+// wrapped u64 is a length, written with the configured length width.
+pub(crate) const LEN: &str = "$serde_bench::private::Len";
+
+/// Numeric types that can be encoded in bulk: every integer type other than
+/// `usize` and `isize`, and `f32` and `f64`.
+///
+/// This trait is sealed and cannot be implemented outside of serde-bench.
+pub trait Primitive: private::Sealed + Copy + Default {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! primitive {
+    ($($ty:ty)*) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl Primitive for $ty {}
+        )*
+    };
+}
+
+primitive!(u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64);
+
+/// Serializes a slice as its length followed by the native-endian bytes of
+/// its elements.
+pub fn serialize<T, S>(value: &[T], serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: Primitive,
+    S: Serializer,
+{
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&Len(value.len() as u64))?;
+    tuple.serialize_element(&Raw(as_bytes(value)))?;
+    tuple.end()
+}
+
+/// Deserializes a `Vec` written by [`serialize`] or element by element.
+pub fn deserialize<'de, T, D>(deserializer: D) -> std::result::Result<Vec<T>, D::Error>
+where
+    T: Primitive,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_tuple(2, VecVisitor(PhantomData))
+}
+
+/// Bulk encoding of fixed-size arrays, which have no length prefix.
+pub mod array {
+    use super::{as_bytes, Primitive, Raw, RawSeed};
+    use serde::{Deserializer, Serialize, Serializer};
+    use std::{mem, ptr};
+
+    pub fn serialize<T, S, const N: usize>(value: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Primitive,
+        S: Serializer,
+    {
+        Raw(as_bytes(value)).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        T: Primitive,
+        D: Deserializer<'de>,
+    {
+        let seed = RawSeed {
+            len: N * mem::size_of::<T>(),
+        };
+        let bytes = serde::de::DeserializeSeed::deserialize(seed, deserializer)?;
+        let mut array = [T::default(); N];
+        // `RawSeed` returns exactly `N * size_of::<T>()` bytes, and every bit
+        // pattern is a valid `Primitive`.
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), array.as_mut_ptr().cast::<u8>(), bytes.len());
+        }
+        Ok(array)
+    }
+}
+
+fn as_bytes<T: Primitive>(value: &[T]) -> &[u8] {
+    // Primitives have no padding, so every byte is initialized.
+    unsafe { slice::from_raw_parts(value.as_ptr().cast::<u8>(), mem::size_of_val(value)) }
+}
+
+fn to_vec<T: Primitive>(bytes: &[u8]) -> Vec<T> {
+    let len = bytes.len() / mem::size_of::<T>();
+    let mut vec = Vec::<T>::with_capacity(len);
+    // Every bit pattern is a valid `Primitive`, and the copy fills exactly
+    // `len` elements.
+    unsafe {
+        ptr::copy_nonoverlapping(
+            bytes.as_ptr(),
+            vec.as_mut_ptr().cast::<u8>(),
+            len * mem::size_of::<T>(),
+        );
+        vec.set_len(len);
+    }
+    vec
+}
+
+pub(crate) struct Len(pub(crate) u64);
+
+impl Serialize for Len {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(LEN, &self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Len {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(LEN, LenVisitor)
+    }
+}
+
+struct LenVisitor;
+
+impl<'de> Visitor<'de> for LenVisitor {
+    type Value = Len;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a length")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Len, E> {
+        Ok(Len(v))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Len, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u64::deserialize(deserializer).map(Len)
+    }
+}
+
+pub(crate) struct Raw<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Serialize for Raw<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(RAW, &RawBytes(self.0))
+    }
+}
+
+struct RawBytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for RawBytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct VecVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for VecVisitor<T>
+where
+    T: Primitive,
+{
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a length-prefixed sequence of primitives")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Vec<T>, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Some(Len(len)) = seq.next_element()? else {
+            return Err(de::Error::invalid_length(0, &self));
+        };
+        let len = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(mem::size_of::<T>()))
+            .ok_or_else(|| de::Error::custom("length overflows usize"))?;
+        let seed = RawSeed { len };
+        let Some(bytes) = seq.next_element_seed(seed)? else {
+            return Err(de::Error::invalid_length(1, &self));
+        };
+        Ok(to_vec(&bytes))
+    }
+}
+
+// Reads `len` bytes written by `Raw`.
+struct RawSeed {
+    len: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for RawSeed {
+    type Value = Cow<'de, [u8]>;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(RAW, RawVisitor { len: self.len })
+    }
+}
+
+struct RawVisitor {
+    len: usize,
+}
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = Cow<'de, [u8]>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} bytes", self.len)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(self.len, self)
+    }
+
+    fn visit_borrowed_bytes<E>(self, bytes: &'de [u8]) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if bytes.len() != self.len {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(Cow::Borrowed(bytes))
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if bytes.len() != self.len {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(Cow::Owned(bytes.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if bytes.len() != self.len {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(self.len.min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_byte_buf(bytes)
+    }
+}
+
+// What the serde-bench Serializer serializes the value of a `RAW` newtype
+// with: the bytes, without a length prefix.
+pub(crate) struct RawSerializer<'a, W> {
+    pub(crate) writer: &'a mut W,
+}
+
+macro_rules! unsupported {
+    ($msg:literal: $($method:ident($($arg:ty),*) -> $ret:ty,)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ret> {
+                Err(Error::new($msg))
+            }
+        )*
+    };
+}
+
+impl<'a, W> Serializer for RawSerializer<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.writer.write_all(v).map_err(From::from)
+    }
+
+    unsupported! {
+        "expected raw bytes":
+        serialize_bool(bool) -> (),
+        serialize_i8(i8) -> (),
+        serialize_i16(i16) -> (),
+        serialize_i32(i32) -> (),
+        serialize_i64(i64) -> (),
+        serialize_i128(i128) -> (),
+        serialize_u8(u8) -> (),
+        serialize_u16(u16) -> (),
+        serialize_u32(u32) -> (),
+        serialize_u64(u64) -> (),
+        serialize_u128(u128) -> (),
+        serialize_f32(f32) -> (),
+        serialize_f64(f64) -> (),
+        serialize_char(char) -> (),
+        serialize_str(&str) -> (),
+        serialize_none() -> (),
+        serialize_unit() -> (),
+        serialize_unit_struct(&'static str) -> (),
+        serialize_unit_variant(&'static str, u32, &'static str) -> (),
+        serialize_seq(Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+        serialize_map(Option<usize>) -> Self::SerializeMap,
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct,
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected raw bytes"))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected raw bytes"))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected raw bytes"))
+    }
+}
+
+// What the serde-bench serializers serialize the value of a `LEN` newtype
+// with, to get the length back out.
+pub(crate) struct LenSerializer;
+
+impl Serializer for LenSerializer {
+    type Ok = u64;
+    type Error = Error;
+    type SerializeSeq = Impossible<u64, Error>;
+    type SerializeTuple = Impossible<u64, Error>;
+    type SerializeTupleStruct = Impossible<u64, Error>;
+    type SerializeTupleVariant = Impossible<u64, Error>;
+    type SerializeMap = Impossible<u64, Error>;
+    type SerializeStruct = Impossible<u64, Error>;
+    type SerializeStructVariant = Impossible<u64, Error>;
+
+    fn serialize_u64(self, v: u64) -> Result<u64> {
+        Ok(v)
+    }
+
+    unsupported! {
+        "expected a length":
+        serialize_bool(bool) -> u64,
+        serialize_i8(i8) -> u64,
+        serialize_i16(i16) -> u64,
+        serialize_i32(i32) -> u64,
+        serialize_i64(i64) -> u64,
+        serialize_i128(i128) -> u64,
+        serialize_u8(u8) -> u64,
+        serialize_u16(u16) -> u64,
+        serialize_u32(u32) -> u64,
+        serialize_u128(u128) -> u64,
+        serialize_f32(f32) -> u64,
+        serialize_f64(f64) -> u64,
+        serialize_char(char) -> u64,
+        serialize_str(&str) -> u64,
+        serialize_bytes(&[u8]) -> u64,
+        serialize_none() -> u64,
+        serialize_unit() -> u64,
+        serialize_unit_struct(&'static str) -> u64,
+        serialize_unit_variant(&'static str, u32, &'static str) -> u64,
+        serialize_seq(Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+        serialize_map(Option<usize>) -> Self::SerializeMap,
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct,
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<u64>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected a length"))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<u64>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected a length"))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<u64>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::new("expected a length"))
+    }
+}
+
+// What the serde-bench Deserializer hands to the visitor of a `RAW` newtype:
+// `deserialize_tuple(len)` reads `len` bytes without a length prefix.
+pub(crate) struct RawDeserializer<'a, R> {
+    pub(crate) read: &'a mut R,
+}
+
+impl<'de, 'a, R> Deserializer<'de> for RawDeserializer<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("expected raw bytes"))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read.read_bytes(len)? {
+            Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Reference::Copied(bytes) => visitor.visit_bytes(bytes),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
@@ -0,0 +1,120 @@
+//! Zero-copy `Bytes` fields, for use with `#[serde(with = "...")]`.
+//!
+//! The `Deserialize` impl of `bytes::Bytes` copies every field into a new
+//! allocation. When the input was deserialized by
+//! [`deserialize_from_bytes`](crate::deserialize_from_bytes), these functions
+//! instead return a slice of the input `Bytes` that shares its reference
+//! count.
+//!
+//! ```
+//! # use bytes::Bytes;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Packet {
+//!     id: u32,
+//!     #[serde(with = "serde_bench::bytes")]
+//!     payload: Bytes,
+//! }
+//! ```
+//!
+//! The encoding is the same as that of a `Vec<u8>` with `serde_bytes`. With
+//! other deserializers, or other input, the field is copied. Like
+//! [`deserialize`](crate::deserialize), `deserialize_from_bytes` expects the
+//! default [`Config`](crate::Config).
+
+use ::bytes::Bytes;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::Serializer;
+use std::cell::RefCell;
+use std::fmt;
+
+thread_local! {
+    // The input of the innermost `deserialize_from_bytes` on this thread.
+    static SOURCE: RefCell<Option<Bytes>> = const { RefCell::new(None) };
+}
+
+pub fn serialize<S>(value: &Bytes, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(value)
+}
+
+/// Deserializes a byte string as a slice of the input `Bytes` if it was
+/// borrowed from there, and as a copy otherwise.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Bytes, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_byte_buf(BytesVisitor)
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        let shared = SOURCE.with(|source| {
+            let source = source.borrow();
+            let source = source.as_ref()?;
+            let range = source.as_ptr_range();
+            let inside = range.start <= v.as_ptr() && v.as_ptr_range().end <= range.end;
+            inside.then(|| source.slice_ref(v))
+        });
+        Ok(shared.unwrap_or_else(|| Bytes::copy_from_slice(v)))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        Ok(Bytes::copy_from_slice(v))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Bytes, E>
+    where
+        E: de::Error,
+    {
+        Ok(Bytes::from(v))
+    }
+
+    // Formats like JSON that write byte strings as a seq of integers.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Bytes, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            vec.push(byte);
+        }
+        Ok(Bytes::from(vec))
+    }
+}
+
+/// Makes `bytes` the input that [`deserialize`] slices from until dropped,
+/// then restores the input of any enclosing `deserialize_from_bytes`.
+pub(crate) struct SourceGuard {
+    previous: Option<Bytes>,
+}
+
+impl SourceGuard {
+    pub(crate) fn new(bytes: &Bytes) -> Self {
+        let previous = SOURCE.with(|source| source.replace(Some(bytes.clone())));
+        SourceGuard { previous }
+    }
+}
+
+impl Drop for SourceGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SOURCE.with(|source| *source.borrow_mut() = previous);
+    }
+}
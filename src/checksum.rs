@@ -0,0 +1,62 @@
+use crate::{Error, ErrorKind, Result};
+
+/// Checksum appended to a serialized payload, in native byte order.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Checksum {
+    /// CRC-32 (IEEE), 4 bytes.
+    #[cfg(feature = "crc32fast")]
+    Crc32,
+    /// XXH64 with seed 0, 8 bytes.
+    #[cfg(feature = "xxhash-rust")]
+    XxHash64,
+}
+
+pub(crate) struct Trailer {
+    buf: [u8; 8],
+    len: usize,
+}
+
+impl Checksum {
+    pub(crate) fn len(self) -> usize {
+        match self {
+            #[cfg(feature = "crc32fast")]
+            Checksum::Crc32 => 4,
+            #[cfg(feature = "xxhash-rust")]
+            Checksum::XxHash64 => 8,
+        }
+    }
+
+    pub(crate) fn trailer(self, payload: &[u8]) -> Trailer {
+        let mut buf = [0; 8];
+        let len = self.len();
+        match self {
+            #[cfg(feature = "crc32fast")]
+            Checksum::Crc32 => {
+                buf[..4].copy_from_slice(&crc32fast::hash(payload).to_ne_bytes());
+            }
+            #[cfg(feature = "xxhash-rust")]
+            Checksum::XxHash64 => {
+                buf.copy_from_slice(&xxhash_rust::xxh64::xxh64(payload, 0).to_ne_bytes());
+            }
+        }
+        Trailer { buf, len }
+    }
+
+    pub(crate) fn verify(self, payload: &[u8], trailer: &[u8]) -> Result<()> {
+        if self.trailer(payload).as_slice() == trailer {
+            Ok(())
+        } else {
+            Err(Error::with_kind(
+                ErrorKind::ChecksumMismatch,
+                format_args!("{self:?} checksum mismatch"),
+            ))
+        }
+    }
+}
+
+impl Trailer {
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
@@ -0,0 +1,126 @@
+use crate::varint;
+use crate::{Error, Result};
+use byteorder::{NativeEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{self, Write};
+
+/// How an integer in the framing of a value is encoded.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Width {
+    U8,
+    U16,
+    U32,
+    U64,
+    /// LEB128, 7 bits per byte, least significant group first.
+    Varint,
+}
+
+/// Widths of the integers the format writes besides the data itself: enum
+/// variant indices and the lengths of strings, byte arrays, seqs and maps.
+///
+/// Values that do not fit in the configured width are rejected when
+/// serializing rather than truncated. Data must be deserialized with the same
+/// config it was serialized with.
+///
+/// Three entry points take a config:
+/// [`serialize_with_config`](crate::serialize_with_config),
+/// [`deserialize_with_config`](crate::deserialize_with_config) and
+/// [`VecSerializer::config`](crate::VecSerializer::config). Streams, frames, the async and `Bytes` entry points, memory-mapped files,
+/// [`dump`](crate::dump) and transcoding all use the default from
+/// [`Config::new`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Config {
+    pub(crate) variant_index: Width,
+    pub(crate) length: Width,
+}
+
+impl Config {
+    /// `u32` variant indices and `u64` lengths, as written by
+    /// [`serialize`](crate::serialize).
+    #[must_use]
+    pub fn new() -> Self {
+        Config {
+            variant_index: Width::U32,
+            length: Width::U64,
+        }
+    }
+
+    #[must_use]
+    pub fn variant_index(mut self, width: Width) -> Self {
+        self.variant_index = width;
+        self
+    }
+
+    #[must_use]
+    pub fn length(mut self, width: Width) -> Self {
+        self.length = width;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn write_variant_index<W: Write>(self, writer: &mut W, index: u32) -> Result<()> {
+        write(
+            writer,
+            self.variant_index,
+            u64::from(index),
+            "variant index",
+        )
+    }
+
+    #[inline]
+    pub(crate) fn write_len<W: Write>(self, writer: &mut W, len: u64) -> Result<()> {
+        write(writer, self.length, len, "length")
+    }
+
+    #[inline]
+    pub(crate) fn read_variant_index<R: io::Read>(self, reader: &mut R) -> Result<u64> {
+        read(reader, self.variant_index)
+    }
+
+    #[inline]
+    pub(crate) fn read_len<R: io::Read>(self, reader: &mut R) -> Result<u64> {
+        read(reader, self.length)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
+
+#[inline]
+fn write<W: Write>(writer: &mut W, width: Width, n: u64, what: &str) -> Result<()> {
+    match width {
+        Width::U8 => {
+            let n = u8::try_from(n).map_err(|_| out_of_range(what, n, "u8"))?;
+            writer.write_u8(n)?;
+        }
+        Width::U16 => {
+            let n = u16::try_from(n).map_err(|_| out_of_range(what, n, "u16"))?;
+            writer.write_u16::<NativeEndian>(n)?;
+        }
+        Width::U32 => {
+            let n = u32::try_from(n).map_err(|_| out_of_range(what, n, "u32"))?;
+            writer.write_u32::<NativeEndian>(n)?;
+        }
+        Width::U64 => writer.write_u64::<NativeEndian>(n)?,
+        Width::Varint => varint::write(writer, n)?,
+    }
+    Ok(())
+}
+
+#[cold]
+fn out_of_range(what: &str, n: u64, ty: &str) -> Error {
+    Error::new(format!("{what} {n} out of range for {ty}"))
+}
+
+#[inline]
+fn read<R: io::Read>(reader: &mut R, width: Width) -> Result<u64> {
+    Ok(match width {
+        Width::U8 => u64::from(reader.read_u8()?),
+        Width::U16 => u64::from(reader.read_u16::<NativeEndian>()?),
+        Width::U32 => u64::from(reader.read_u32::<NativeEndian>()?),
+        Width::U64 => reader.read_u64::<NativeEndian>()?,
+        Width::Varint => varint::read(reader)?,
+    })
+}
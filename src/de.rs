@@ -1,31 +1,68 @@
+use crate::bulk::{RawDeserializer, LEN, RAW};
+use crate::config::Config;
+use crate::read::{self, Read, Reference};
 use crate::{Error, Result};
 use byteorder::{NativeEndian, ReadBytesExt};
 use serde::de::{
-    self, Deserialize, DeserializeSeed, EnumAccess, IntoDeserializer, SeqAccess, VariantAccess,
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, SeqAccess, Unexpected, VariantAccess,
     Visitor,
 };
 use std::str;
 
-pub struct Deserializer<'de> {
-    bytes: &'de [u8],
+pub struct Deserializer<R> {
+    pub(crate) read: R,
+    config: Config,
 }
 
-impl<'de> Deserializer<'de> {
-    pub fn new(bytes: &'de [u8]) -> Self {
-        Deserializer { bytes }
+impl<'de, R> Deserializer<R>
+where
+    R: Read<'de>,
+{
+    pub fn new(read: R) -> Self {
+        Deserializer::with_config(read, Config::new())
+    }
+
+    pub fn with_config(read: R, config: Config) -> Self {
+        Deserializer { read, config }
     }
 
     #[inline]
-    fn read_slice(&mut self) -> Result<&'de [u8]> {
-        let len = Deserialize::deserialize(&mut *self)?;
-        let (slice, rest) = self.bytes.split_at(len);
-        self.bytes = rest;
-        Ok(slice)
+    pub(crate) fn read_len(&mut self) -> Result<usize> {
+        let len = self.config.read_len(&mut self.read)?;
+        usize::try_from(len)
+            .map_err(|_| de::Error::invalid_value(Unexpected::Unsigned(len), &"usize"))
     }
 
     #[inline]
-    fn read_str(&mut self) -> Result<&'de str> {
-        str::from_utf8(self.read_slice()?).map_err(Into::into)
+    pub(crate) fn read_variant_index(&mut self) -> Result<u32> {
+        let index = self.config.read_variant_index(&mut self.read)?;
+        u32::try_from(index)
+            .map_err(|_| de::Error::invalid_value(Unexpected::Unsigned(index), &"u32"))
+    }
+
+    // The string and byte string halves of `deserialize_str` and
+    // `deserialize_bytes`, after the length has been read.
+    #[inline]
+    pub(crate) fn visit_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let validate = self.read.validate_utf8();
+        match self.read.read_bytes(len)? {
+            Reference::Borrowed(bytes) => visitor.visit_borrowed_str(parse_str(validate, bytes)?),
+            Reference::Copied(bytes) => visitor.visit_str(parse_str(validate, bytes)?),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn visit_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.read.read_bytes(len)? {
+            Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Reference::Copied(bytes) => visitor.visit_bytes(bytes),
+        }
     }
 }
 
@@ -36,13 +73,16 @@ macro_rules! impl_nums {
         where
             V: Visitor<'de>,
         {
-            let value = self.bytes.$reader_method::<NativeEndian>()?;
+            let value = self.read.$reader_method::<NativeEndian>()?;
             visitor.$visitor_method(value)
         }
     };
 }
 
-impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
+impl<'de, R> serde::Deserializer<'de> for &mut Deserializer<R>
+where
+    R: Read<'de>,
+{
     type Error = Error;
 
     fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
@@ -57,7 +97,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        match self.bytes.read_u8()? {
+        match self.read.read_u8()? {
             1 => visitor.visit_bool(true),
             0 => visitor.visit_bool(false),
             _ => Err(Error::new("invalid boolean")),
@@ -80,7 +120,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u8(self.bytes.read_u8()?)
+        visitor.visit_u8(self.read.read_u8()?)
     }
 
     #[inline]
@@ -88,7 +128,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_i8(self.bytes.read_i8()?)
+        visitor.visit_i8(self.read.read_i8()?)
     }
 
     #[inline]
@@ -104,21 +144,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        let width = utf8_char_width(self.bytes[0]);
-        if width == 1 {
-            return visitor.visit_char(self.bytes[0] as char);
-        }
-        if width == 0 {
-            return Err(Error::new("invalid char"));
-        }
-        let res = match str::from_utf8(&self.bytes[..width]) {
-            Ok(s) => s.chars().next().unwrap(),
-            Err(err) => {
-                return Err(err.into());
-            }
-        };
-        self.bytes = &self.bytes[width..];
-        visitor.visit_char(res)
+        visitor.visit_char(self.read.read_char()?)
     }
 
     #[inline]
@@ -126,7 +152,8 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_borrowed_str(self.read_str()?)
+        let len = self.read_len()?;
+        self.visit_str(len, visitor)
     }
 
     #[inline]
@@ -134,7 +161,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_borrowed_str(self.read_str()?)
+        self.deserialize_str(visitor)
     }
 
     #[inline]
@@ -142,7 +169,8 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_borrowed_bytes(self.read_slice()?)
+        let len = self.read_len()?;
+        self.visit_bytes(len, visitor)
     }
 
     #[inline]
@@ -150,7 +178,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_borrowed_bytes(self.read_slice()?)
+        self.deserialize_bytes(visitor)
     }
 
     #[inline]
@@ -179,7 +207,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        match self.bytes.read_u8()? {
+        match self.read.read_u8()? {
             0 => visitor.visit_none(),
             1 => visitor.visit_some(self),
             _ => Err(Error::new("invalid Option")),
@@ -191,12 +219,15 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        struct SeqAccess<'a, 'de: 'a> {
-            deserializer: &'a mut Deserializer<'de>,
+        struct SeqAccess<'a, R> {
+            deserializer: &'a mut Deserializer<R>,
             remaining: usize,
         }
 
-        impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'a, 'de> {
+        impl<'de, 'a, R> de::SeqAccess<'de> for SeqAccess<'a, R>
+        where
+            R: Read<'de>,
+        {
             type Error = Error;
 
             #[inline]
@@ -213,7 +244,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
             }
         }
 
-        let len = Deserialize::deserialize(&mut *self)?;
+        let len = self.read_len()?;
 
         visitor.visit_seq(SeqAccess {
             deserializer: self,
@@ -226,12 +257,15 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        struct MapAccess<'a, 'de: 'a> {
-            deserializer: &'a mut Deserializer<'de>,
+        struct MapAccess<'a, R> {
+            deserializer: &'a mut Deserializer<R>,
             remaining: usize,
         }
 
-        impl<'de, 'a> de::MapAccess<'de> for MapAccess<'a, 'de> {
+        impl<'de, 'a, R> de::MapAccess<'de> for MapAccess<'a, R>
+        where
+            R: Read<'de>,
+        {
             type Error = Error;
 
             #[inline]
@@ -256,7 +290,7 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
             }
         }
 
-        let len = Deserialize::deserialize(&mut *self)?;
+        let len = self.read_len()?;
 
         visitor.visit_map(MapAccess {
             deserializer: self,
@@ -285,10 +319,18 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
     }
 
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == RAW {
+            return visitor.visit_newtype_struct(RawDeserializer {
+                read: &mut self.read,
+            });
+        }
+        if name == LEN {
+            return visitor.visit_u64(self.config.read_len(&mut self.read)?);
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -326,7 +368,10 @@ impl<'de> serde::Deserializer<'de> for &mut Deserializer<'de> {
 }
 
 // For tuples, structs, tuple structs, and fixed size seqs.
-impl<'de> SeqAccess<'de> for Deserializer<'de> {
+impl<'de, R> SeqAccess<'de> for Deserializer<R>
+where
+    R: Read<'de>,
+{
     type Error = Error;
 
     #[inline]
@@ -338,7 +383,10 @@ impl<'de> SeqAccess<'de> for Deserializer<'de> {
     }
 }
 
-impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
+impl<'de, R> EnumAccess<'de> for &mut Deserializer<R>
+where
+    R: Read<'de>,
+{
     type Error = Error;
     type Variant = Self;
 
@@ -347,14 +395,17 @@ impl<'de> EnumAccess<'de> for &mut Deserializer<'de> {
     where
         V: DeserializeSeed<'de>,
     {
-        let index = Deserialize::deserialize(&mut *self)?;
+        let index = self.read_variant_index()?;
         let deserializer = <u32 as IntoDeserializer<Error>>::into_deserializer(index);
         let value = seed.deserialize(deserializer)?;
         Ok((value, self))
     }
 }
 
-impl<'de> VariantAccess<'de> for &mut Deserializer<'de> {
+impl<'de, R> VariantAccess<'de> for &mut Deserializer<R>
+where
+    R: Read<'de>,
+{
     type Error = Error;
 
     #[inline]
@@ -408,6 +459,16 @@ static UTF8_CHAR_WIDTH: [u8; 256] = [
 ];
 
 #[inline]
-fn utf8_char_width(b: u8) -> usize {
+pub(crate) fn utf8_char_width(b: u8) -> usize {
     UTF8_CHAR_WIDTH[b as usize] as usize
 }
+
+#[inline]
+fn parse_str(validate: bool, bytes: &[u8]) -> Result<&str> {
+    if validate {
+        read::from_utf8(bytes)
+    } else {
+        // Only readers built with `skip_utf8_validation` opt out.
+        Ok(unsafe { str::from_utf8_unchecked(bytes) })
+    }
+}
@@ -0,0 +1,603 @@
+//! Annotated dumps of serde-bench bytes, driven by the `Deserialize` impl of
+//! the type they encode.
+
+use crate::bulk::{LEN, RAW};
+use crate::de::Deserializer;
+use crate::read::SliceRead;
+use crate::{Error, Result};
+use serde::de::{
+    self, Deserialize, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use std::fmt::{self, Write as _};
+use std::marker::PhantomData;
+
+// Bytes shown per line before the rest is elided.
+const HEX_BYTES: usize = 8;
+
+/// Describes how `bytes` decode as a `T`, one line per value: its offset, the
+/// bytes it occupies, its field name or index, and what was decoded.
+///
+/// ```
+/// let mut bytes = Vec::new();
+/// serde_bench::serialize(&mut bytes, &(Some("hi"), 7u16)).unwrap();
+/// print!("{}", serde_bench::dump::<(Option<&str>, u16)>(&bytes));
+/// ```
+///
+/// ```text
+/// 00000000                              tuple, len 2
+/// 00000000  01                            [0] Some
+/// 00000001  02 00 00 00 00 00 00 00 ..      str, len 2 = "hi"
+/// 0000000b  07 00                         [1] u16 = 7
+/// ```
+///
+/// Decoding errors and trailing bytes are reported on the last line rather
+/// than failing the dump, since malformed input is what a dump is for. The
+/// bytes are read with the default [`Config`](crate::Config).
+#[must_use]
+pub fn dump<'de, T>(bytes: &'de [u8]) -> String
+where
+    T: Deserialize<'de>,
+{
+    dump_seed(bytes, PhantomData::<T>)
+}
+
+/// Like [`dump`], for types described at runtime by a `DeserializeSeed`.
+pub fn dump_seed<'de, S>(bytes: &'de [u8], seed: S) -> String
+where
+    S: DeserializeSeed<'de>,
+{
+    let mut dumper = Dumper {
+        de: Deserializer::new(SliceRead::new(bytes)),
+        bytes,
+        out: String::new(),
+        error_offset: None,
+    };
+    let result = seed.deserialize(Tracer {
+        dumper: &mut dumper,
+        depth: 0,
+        label: String::new(),
+    });
+    match result {
+        Ok(_) => {
+            let offset = dumper.offset();
+            if offset < bytes.len() {
+                let n = bytes.len() - offset;
+                let s = if n == 1 { "" } else { "s" };
+                dumper.line(
+                    offset,
+                    bytes.len(),
+                    0,
+                    "",
+                    format_args!("{n} trailing byte{s}"),
+                );
+            }
+        }
+        Err(err) => {
+            let offset = dumper.error_offset.unwrap_or_else(|| dumper.offset());
+            let end = bytes.len().min(offset + HEX_BYTES);
+            dumper.line(offset, end, 0, "", format_args!("error: {err}"));
+        }
+    }
+    dumper.out
+}
+
+struct Dumper<'de> {
+    de: Deserializer<SliceRead<'de>>,
+    bytes: &'de [u8],
+    out: String,
+    // Start of the innermost value that failed to decode.
+    error_offset: Option<usize>,
+}
+
+impl<'de> Dumper<'de> {
+    fn offset(&self) -> usize {
+        self.bytes.len() - self.de.read.remaining()
+    }
+
+    fn line(&mut self, start: usize, end: usize, depth: usize, label: &str, desc: fmt::Arguments) {
+        let bytes = &self.bytes[start..end];
+        let mut hex = String::new();
+        for (i, byte) in bytes.iter().take(HEX_BYTES).enumerate() {
+            let sep = if i == 0 { "" } else { " " };
+            let _ = write!(hex, "{sep}{byte:02x}");
+        }
+        if bytes.len() > HEX_BYTES {
+            hex.push_str(" ..");
+        }
+        let indent = depth * 2;
+        let _ = writeln!(
+            self.out,
+            "{start:08x}  {hex:<26}  {:indent$}{label}{desc}",
+            "",
+        );
+    }
+
+    fn fail<T>(&mut self, start: usize, result: Result<T>) -> Result<T> {
+        if result.is_err() && self.error_offset.is_none() {
+            self.error_offset = Some(start);
+        }
+        result
+    }
+}
+
+struct Tracer<'a, 'de> {
+    dumper: &'a mut Dumper<'de>,
+    depth: usize,
+    label: String,
+}
+
+impl<'a, 'de> Tracer<'a, 'de> {
+    fn line(&mut self, start: usize, desc: fmt::Arguments) {
+        let end = self.dumper.offset();
+        self.dumper.line(start, end, self.depth, &self.label, desc);
+    }
+
+    fn compound(
+        &mut self,
+        len: usize,
+        fields: Option<&'static [&'static str]>,
+    ) -> Compound<'_, 'de> {
+        Compound {
+            dumper: self.dumper,
+            depth: self.depth + 1,
+            remaining: len,
+            index: 0,
+            fields,
+        }
+    }
+
+    fn child(&mut self) -> Tracer<'_, 'de> {
+        Tracer {
+            dumper: self.dumper,
+            depth: self.depth + 1,
+            label: String::new(),
+        }
+    }
+
+    fn read<T: Deserialize<'de>>(&mut self, start: usize) -> Result<T> {
+        let result = T::deserialize(&mut self.dumper.de);
+        self.dumper.fail(start, result)
+    }
+}
+
+macro_rules! leaf {
+    ($($method:ident => $ty:literal,)*) => {
+        $(
+            fn $method<V>(mut self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                let start = self.dumper.offset();
+                let mut value = String::new();
+                let result = self.dumper.de.$method(Recorder {
+                    visitor,
+                    value: &mut value,
+                });
+                let result = self.dumper.fail(start, result);
+                if result.is_ok() {
+                    self.line(start, format_args!("{} = {value}", $ty));
+                }
+                result
+            }
+        )*
+    };
+}
+
+impl<'a, 'de> de::Deserializer<'de> for Tracer<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_any` is not supported"))
+    }
+
+    leaf! {
+        deserialize_bool => "bool",
+        deserialize_i8 => "i8",
+        deserialize_i16 => "i16",
+        deserialize_i32 => "i32",
+        deserialize_i64 => "i64",
+        deserialize_i128 => "i128",
+        deserialize_u8 => "u8",
+        deserialize_u16 => "u16",
+        deserialize_u32 => "u32",
+        deserialize_u64 => "u64",
+        deserialize_u128 => "u128",
+        deserialize_f32 => "f32",
+        deserialize_f64 => "f64",
+        deserialize_char => "char",
+    }
+
+    fn deserialize_str<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        let result = self.dumper.de.read_len();
+        let len = self.dumper.fail(start, result)?;
+        let mut value = String::new();
+        let result = self.dumper.de.visit_str(
+            len,
+            Recorder {
+                visitor,
+                value: &mut value,
+            },
+        );
+        let result = self.dumper.fail(start, result);
+        if result.is_ok() {
+            self.line(start, format_args!("str, len {len} = {value}"));
+        }
+        result
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        let result = self.dumper.de.read_len();
+        let len = self.dumper.fail(start, result)?;
+        let mut value = String::new();
+        let result = self.dumper.de.visit_bytes(
+            len,
+            Recorder {
+                visitor,
+                value: &mut value,
+            },
+        );
+        let result = self.dumper.fail(start, result);
+        if result.is_ok() {
+            self.line(start, format_args!("bytes, len {len}"));
+        }
+        result
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        match self.read::<u8>(start)? {
+            0 => {
+                self.line(start, format_args!("None"));
+                visitor.visit_none()
+            }
+            1 => {
+                self.line(start, format_args!("Some"));
+                visitor.visit_some(self.child())
+            }
+            _ => self.dumper.fail(start, Err(Error::new("invalid Option"))),
+        }
+    }
+
+    fn deserialize_unit<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        self.line(start, format_args!("()"));
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        self.line(start, format_args!("{name}"));
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        if name == LEN {
+            let mut value = String::new();
+            let result = self.dumper.de.deserialize_newtype_struct(
+                name,
+                Recorder {
+                    visitor,
+                    value: &mut value,
+                },
+            );
+            let result = self.dumper.fail(start, result);
+            if result.is_ok() {
+                self.line(start, format_args!("len = {value}"));
+            }
+            return result;
+        }
+        if name == RAW {
+            // Only the visitor knows how many bytes a bulk field takes.
+            let result = self.dumper.de.deserialize_newtype_struct(name, visitor);
+            let result = self.dumper.fail(start, result);
+            if result.is_ok() {
+                let len = self.dumper.offset() - start;
+                self.line(start, format_args!("raw, len {len}"));
+            }
+            return result;
+        }
+        self.line(start, format_args!("{name}"));
+        visitor.visit_newtype_struct(self.child())
+    }
+
+    fn deserialize_seq<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        let result = self.dumper.de.read_len();
+        let len = self.dumper.fail(start, result)?;
+        self.line(start, format_args!("seq, len {len}"));
+        visitor.visit_seq(self.compound(len, None))
+    }
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        self.line(start, format_args!("tuple, len {len}"));
+        visitor.visit_seq(self.compound(len, None))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        mut self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        self.line(start, format_args!("{name}, len {len}"));
+        visitor.visit_seq(self.compound(len, None))
+    }
+
+    fn deserialize_map<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        let result = self.dumper.de.read_len();
+        let len = self.dumper.fail(start, result)?;
+        self.line(start, format_args!("map, len {len}"));
+        visitor.visit_map(self.compound(len, None))
+    }
+
+    fn deserialize_struct<V>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        self.line(start, format_args!("{name}"));
+        visitor.visit_seq(self.compound(fields.len(), Some(fields)))
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let start = self.dumper.offset();
+        let result = self.dumper.de.read_variant_index();
+        let index = self.dumper.fail(start, result)?;
+        let variant = variants.get(index as usize).copied().unwrap_or("?");
+        self.line(start, format_args!("{name}::{variant}, index {index}"));
+        visitor.visit_enum(Enum {
+            tracer: self,
+            index,
+        })
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_identifier` is not supported"))
+    }
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_ignored_any` is not supported"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+// Elements of seqs, tuples, structs and maps, labeled by index or field name.
+struct Compound<'a, 'de> {
+    dumper: &'a mut Dumper<'de>,
+    depth: usize,
+    remaining: usize,
+    index: usize,
+    fields: Option<&'static [&'static str]>,
+}
+
+impl<'a, 'de> Compound<'a, 'de> {
+    fn element(&mut self, label: String) -> Tracer<'_, 'de> {
+        Tracer {
+            dumper: self.dumper,
+            depth: self.depth,
+            label,
+        }
+    }
+}
+
+impl<'a, 'de> SeqAccess<'de> for Compound<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let index = self.index;
+        self.index += 1;
+        let label = match self.fields.and_then(|fields| fields.get(index)) {
+            Some(field) => format!("{field}: "),
+            None => format!("[{index}] "),
+        };
+        seed.deserialize(self.element(label)).map(Some)
+    }
+}
+
+impl<'a, 'de> MapAccess<'de> for Compound<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let label = format!("[{}] key: ", self.index);
+        seed.deserialize(self.element(label)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let label = format!("[{}] value: ", self.index);
+        self.index += 1;
+        seed.deserialize(self.element(label))
+    }
+}
+
+struct Enum<'a, 'de> {
+    tracer: Tracer<'a, 'de>,
+    index: u32,
+}
+
+impl<'a, 'de> EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let deserializer = <u32 as IntoDeserializer<Error>>::into_deserializer(self.index);
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'de> VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.tracer.child())
+    }
+
+    fn tuple_variant<V>(mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self.tracer.compound(len, None))
+    }
+
+    fn struct_variant<V>(mut self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self.tracer.compound(fields.len(), Some(fields)))
+    }
+}
+
+// Forwards primitive values to the real visitor, keeping a printable copy.
+struct Recorder<'a, V> {
+    visitor: V,
+    value: &'a mut String,
+}
+
+macro_rules! record {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> std::result::Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                *self.value = format!("{:?}", v);
+                self.visitor.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, V> Visitor<'de> for Recorder<'a, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    record! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+    }
+}
@@ -4,17 +4,39 @@ use std::{error, io, str, string};
 
 #[derive(Debug)]
 pub struct Error {
+    kind: ErrorKind,
     msg: String,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The underlying reader or writer failed.
+    Io,
+    /// The checksum trailer does not match the payload.
+    ChecksumMismatch,
+    /// Invalid input or a `Serialize` or `Deserialize` impl reported an error.
+    Other,
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl Error {
     pub fn new<T: Display>(msg: T) -> Self {
+        Error::with_kind(ErrorKind::Other, msg)
+    }
+
+    pub(crate) fn with_kind<T: Display>(kind: ErrorKind, msg: T) -> Self {
         Error {
+            kind,
             msg: msg.to_string(),
         }
     }
+
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
 }
 
 impl error::Error for Error {}
@@ -39,7 +61,11 @@ impl de::Error for Error {
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
-        Error::new(err)
+        // Running out of input is a problem with the data, not with the I/O.
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return Error::new("unexpected end of input");
+        }
+        Error::with_kind(ErrorKind::Io, err)
     }
 }
 
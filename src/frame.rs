@@ -0,0 +1,252 @@
+//! Length-delimited framing for sending serde-bench messages over sockets,
+//! pipes and files.
+//!
+//! Each frame is a length prefix, the serialized message, and optionally a
+//! checksum of the message. Fixed-width prefixes and checksums are written in
+//! native byte order, like the rest of the format.
+
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+use crate::checksum::Checksum;
+use crate::de::Deserializer;
+use crate::read::{Read as _, SliceRead};
+use crate::varint;
+use crate::{Error, Result};
+use byteorder::{NativeEndian, WriteBytesExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LengthPrefix {
+    U32,
+    U64,
+    /// LEB128, 7 bits per byte, least significant group first.
+    Varint,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct FrameConfig {
+    length_prefix: LengthPrefix,
+    max_frame_size: Option<usize>,
+    #[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+    checksum: Option<Checksum>,
+}
+
+impl FrameConfig {
+    /// Fixed 64-bit length prefix, no size limit and no checksum.
+    #[must_use]
+    pub fn new() -> Self {
+        FrameConfig {
+            length_prefix: LengthPrefix::U64,
+            max_frame_size: None,
+            #[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+            checksum: None,
+        }
+    }
+
+    #[must_use]
+    pub fn length_prefix(mut self, length_prefix: LengthPrefix) -> Self {
+        self.length_prefix = length_prefix;
+        self
+    }
+
+    /// Rejects messages longer than `max` bytes, both when writing and before
+    /// reading their payload.
+    #[must_use]
+    pub fn max_frame_size(mut self, max: usize) -> Self {
+        self.max_frame_size = Some(max);
+        self
+    }
+
+    /// Appends a checksum of each message and verifies it when reading.
+    #[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+    #[must_use]
+    pub fn checksum(mut self, checksum: Checksum) -> Self {
+        self.checksum = Some(checksum);
+        self
+    }
+
+    fn check_size(&self, len: u64) -> Result<()> {
+        match self.max_frame_size {
+            Some(max) if len > max as u64 => Err(Error::new(format!(
+                "frame of {len} bytes exceeds the maximum of {max} bytes",
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for FrameConfig {
+    fn default() -> Self {
+        FrameConfig::new()
+    }
+}
+
+/// Writes values as frames described by a [`FrameConfig`]. The messages
+/// inside are encoded with the default [`Config`](crate::Config), whatever the
+/// frame's own length prefix.
+pub struct FrameWriter<W> {
+    writer: W,
+    config: FrameConfig,
+    buf: Vec<u8>,
+}
+
+impl<W> FrameWriter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        FrameWriter::with_config(writer, FrameConfig::new())
+    }
+
+    pub fn with_config(writer: W, config: FrameConfig) -> Self {
+        FrameWriter {
+            writer,
+            config,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Writes `value` as one frame. Nothing is written if serialization fails
+    /// or the message is over the size limit.
+    pub fn write<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.buf.clear();
+        crate::serialize(&mut self.buf, &value)?;
+        let len = self.buf.len() as u64;
+        self.config.check_size(len)?;
+
+        match self.config.length_prefix {
+            LengthPrefix::U32 => {
+                let len = u32::try_from(len)
+                    .map_err(|_| Error::new("frame too long for a u32 length prefix"))?;
+                self.writer.write_u32::<NativeEndian>(len)?;
+            }
+            LengthPrefix::U64 => self.writer.write_u64::<NativeEndian>(len)?,
+            LengthPrefix::Varint => varint::write(&mut self.writer, len)?,
+        }
+        self.writer.write_all(&self.buf)?;
+        #[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+        if let Some(checksum) = self.config.checksum {
+            let trailer = checksum.trailer(&self.buf);
+            self.writer.write_all(trailer.as_slice())?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(From::from)
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads frames written by a [`FrameWriter`] with the same [`FrameConfig`].
+pub struct FrameReader<R> {
+    reader: R,
+    config: FrameConfig,
+    buf: Vec<u8>,
+}
+
+impl<R> FrameReader<R>
+where
+    R: Read,
+{
+    pub fn new(reader: R) -> Self {
+        FrameReader::with_config(reader, FrameConfig::new())
+    }
+
+    pub fn with_config(reader: R, config: FrameConfig) -> Self {
+        FrameReader {
+            reader,
+            config,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Reads the next frame, or returns `None` if the reader is at end of
+    /// input where a frame would start.
+    pub fn read<T>(&mut self) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let Some(len) = self.read_len()? else {
+            return Ok(None);
+        };
+        self.config.check_size(len)?;
+
+        // Grows with the bytes actually received rather than trusting `len`.
+        self.buf.clear();
+        (&mut self.reader).take(len).read_to_end(&mut self.buf)?;
+        if (self.buf.len() as u64) < len {
+            return Err(Error::new("unexpected end of input"));
+        }
+
+        #[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+        if let Some(checksum) = self.config.checksum {
+            let mut trailer = [0; 8];
+            let trailer = &mut trailer[..checksum.len()];
+            self.reader.read_exact(trailer)?;
+            checksum.verify(&self.buf, trailer)?;
+        }
+
+        let mut de = Deserializer::new(SliceRead::new(&self.buf));
+        let value = T::deserialize(&mut de)?;
+        if !de.read.at_end()? {
+            return Err(Error::new("trailing bytes in frame"));
+        }
+        Ok(Some(value))
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    // Distinguishes a clean end of input before the prefix from one inside it.
+    fn read_len(&mut self) -> Result<Option<u64>> {
+        let mut first = 0;
+        loop {
+            match self.reader.read(std::slice::from_mut(&mut first)) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let len = match self.config.length_prefix {
+            LengthPrefix::U32 => {
+                let mut buf = [first, 0, 0, 0];
+                self.reader.read_exact(&mut buf[1..])?;
+                u64::from(u32::from_ne_bytes(buf))
+            }
+            LengthPrefix::U64 => {
+                let mut buf = [first, 0, 0, 0, 0, 0, 0, 0];
+                self.reader.read_exact(&mut buf[1..])?;
+                u64::from_ne_bytes(buf)
+            }
+            LengthPrefix::Varint => varint::read_after(&mut self.reader, first)?,
+        };
+        Ok(Some(len))
+    }
+}
@@ -8,15 +8,47 @@
 
 extern crate serde_core as serde;
 
+#[cfg(feature = "tokio")]
+mod async_io;
+pub mod bulk;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+mod checksum;
+mod config;
 mod de;
+mod dump;
 mod error;
+mod frame;
+#[cfg(feature = "memmap2")]
+mod mmap;
+pub mod read;
 mod ser;
+mod stream;
+#[cfg(feature = "test-util")]
+pub mod token;
+mod transcode;
+mod varint;
+mod vec;
 
 use self::de::Deserializer;
+use self::read::SliceRead;
 use self::ser::Serializer;
 use serde::{Deserialize, Serialize};
 
-pub use self::error::{Error, Result};
+#[cfg(feature = "tokio")]
+pub use self::async_io::{deserialize_async, serialize_async};
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+pub use self::checksum::Checksum;
+pub use self::config::{Config, Width};
+pub use self::dump::{dump, dump_seed};
+pub use self::error::{Error, ErrorKind, Result};
+pub use self::frame::{FrameConfig, FrameReader, FrameWriter, LengthPrefix};
+#[cfg(feature = "memmap2")]
+pub use self::mmap::{open_file, MappedFile};
+pub use self::stream::StreamDeserializer;
+pub use self::transcode::{transcode_from, transcode_to};
+pub use self::vec::VecSerializer;
 
 pub fn serialize<T>(out: &mut Vec<u8>, value: &T) -> Result<()>
 where
@@ -26,10 +58,155 @@ where
     Serialize::serialize(value, &mut ser)
 }
 
+/// Like [`serialize`], but reserves `size_hint` bytes once and then writes
+/// through [`VecSerializer`] rather than `io::Write`.
+pub fn serialize_with_size_hint<T>(out: &mut Vec<u8>, value: &T, size_hint: usize) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut ser = VecSerializer::with_size_hint(out, size_hint);
+    Serialize::serialize(value, &mut ser)
+}
+
+/// Like [`serialize`], with the variant index and length widths from
+/// `config`.
+pub fn serialize_with_config<T>(out: &mut Vec<u8>, value: &T, config: Config) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut ser = Serializer::with_config(out, config);
+    Serialize::serialize(value, &mut ser)
+}
+
+/// Appends `value` to any `BufMut`, such as a `BytesMut`, without an
+/// intermediate `Vec`.
+///
+/// A `BufMut` cannot be truncated, so if serialization fails, whatever was
+/// written before the error stays in `buf`. A buffer with fixed capacity
+/// fails with [`ErrorKind::Io`] once it is full.
+#[cfg(feature = "bytes")]
+pub fn serialize_into_buf<B, T>(buf: &mut B, value: &T) -> Result<()>
+where
+    B: ::bytes::BufMut + ?Sized,
+    T: Serialize,
+{
+    let mut ser = Serializer::new(::bytes::BufMut::writer(buf));
+    Serialize::serialize(value, &mut ser)
+}
+
 pub fn deserialize<'de, T>(bytes: &'de [u8]) -> Result<T>
 where
     T: Deserialize<'de>,
 {
-    let mut de = Deserializer::new(bytes);
+    let mut de = Deserializer::new(SliceRead::new(bytes));
     Deserialize::deserialize(&mut de)
 }
+
+/// Deserializes from a `Bytes`, so that fields using
+/// [`serde_bench::bytes`](crate::bytes) become slices of `bytes` rather than
+/// copies. Other borrowed fields borrow from `bytes` as with [`deserialize`].
+#[cfg(feature = "bytes")]
+pub fn deserialize_from_bytes<'de, T>(bytes: &'de ::bytes::Bytes) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let _source = self::bytes::SourceGuard::new(bytes);
+    deserialize(bytes)
+}
+
+/// Deserializes data written by [`serialize_with_config`] with the same
+/// `config`.
+pub fn deserialize_with_config<'de, T>(bytes: &'de [u8], config: Config) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = Deserializer::with_config(SliceRead::new(bytes), config);
+    Deserialize::deserialize(&mut de)
+}
+
+/// Deserializes into an existing value, reusing its allocations where the
+/// type's `Deserialize` impl supports it. The standard library impls reuse the
+/// buffers of `String` and `Vec`, and derived impls do the same for each field
+/// when `serde_derive` is built with its `deserialize_in_place` feature.
+pub fn deserialize_in_place<'de, T>(bytes: &'de [u8], place: &mut T) -> Result<()>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = Deserializer::new(SliceRead::new(bytes));
+    Deserialize::deserialize_in_place(&mut de, place)
+}
+
+/// Like [`deserialize`], but borrows strings without checking that they are
+/// UTF-8. Reads are bounds checked as usual, so truncated or malformed input
+/// is still an error rather than undefined behavior.
+///
+/// Strings are still handed to `visit_borrowed_str`, since passing their
+/// bytes to `visit_borrowed_bytes` instead would make `&str` and `String`
+/// visitors either reject them or validate them anyway.
+///
+/// # Safety
+///
+/// Every string in `bytes` must be valid UTF-8.
+pub unsafe fn deserialize_trusted<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let read = SliceRead::new(bytes).skip_utf8_validation();
+    let mut de = Deserializer::new(read);
+    Deserialize::deserialize(&mut de)
+}
+
+/// Serializes `value` preceded by its encoded length as a u64, so that a
+/// [`StreamDeserializer`] in framed mode can find where each record ends.
+///
+/// This is the frame format of a default [`FrameConfig`]: a native-endian u64
+/// length prefix and no checksum. A [`FrameReader`] with the default config
+/// reads these frames, and a [`StreamDeserializer`] in framed mode reads those
+/// of a default [`FrameWriter`]. Other length prefixes and checksums are only
+/// supported by [`FrameWriter`] and [`FrameReader`].
+pub fn serialize_framed<T>(out: &mut Vec<u8>, value: &T) -> Result<()>
+where
+    T: Serialize,
+{
+    let start = out.len();
+    out.extend_from_slice(&[0; 8]);
+    if let Err(err) = serialize(out, value) {
+        out.truncate(start);
+        return Err(err);
+    }
+    let len = (out.len() - start - 8) as u64;
+    out[start..start + 8].copy_from_slice(&len.to_ne_bytes());
+    Ok(())
+}
+
+/// Serializes `value` followed by a checksum of the serialized bytes.
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+pub fn serialize_with_checksum<T>(out: &mut Vec<u8>, value: &T, checksum: Checksum) -> Result<()>
+where
+    T: Serialize,
+{
+    let start = out.len();
+    if let Err(err) = serialize(out, value) {
+        out.truncate(start);
+        return Err(err);
+    }
+    let trailer = checksum.trailer(&out[start..]);
+    out.extend_from_slice(trailer.as_slice());
+    Ok(())
+}
+
+/// Verifies the checksum at the end of `bytes`, failing with
+/// [`ErrorKind::ChecksumMismatch`] if the payload was corrupted, and then
+/// deserializes the payload.
+#[cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+pub fn deserialize_with_checksum<'de, T>(bytes: &'de [u8], checksum: Checksum) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let Some(split) = bytes.len().checked_sub(checksum.len()) else {
+        return Err(Error::new("unexpected end of input"));
+    };
+    let (payload, trailer) = bytes.split_at(split);
+    checksum.verify(payload, trailer)?;
+    deserialize(payload)
+}
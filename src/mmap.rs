@@ -0,0 +1,81 @@
+//! Deserializing from memory-mapped files, enabled by the `memmap2` feature.
+//!
+//! The file is mapped read-only and deserialized in place like any other
+//! slice, so a multi-gigabyte capture is paged in as it is read rather than
+//! copied into memory up front. Borrowed `&str` and `&[u8]` fields point
+//! straight into the mapping and are tied to the lifetime of the
+//! [`MappedFile`]. Files must have been written with the default
+//! [`Config`](crate::Config).
+
+use crate::read::SliceRead;
+use crate::{Result, StreamDeserializer};
+use memmap2::Mmap;
+use serde::Deserialize;
+use std::fs::File;
+use std::path::Path;
+
+/// A file mapped read-only into memory, for deserializing large inputs without
+/// reading them into a buffer first.
+///
+/// Borrowed `&str` and `&[u8]` fields of the values deserialized from it point
+/// into the mapping, so they can outlive neither the `MappedFile` nor any
+/// change to the file.
+pub struct MappedFile {
+    mmap: Mmap,
+}
+
+/// Maps the file at `path` read-only.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated, by this process or any other,
+/// while the `MappedFile` or anything borrowed from it is alive. The contents
+/// of a mapping change along with the file, which would break the guarantees
+/// of every `&str` and `&[u8]` borrowed from it.
+pub unsafe fn open_file<P>(path: P) -> Result<MappedFile>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    let mmap = Mmap::map(&file)?;
+    Ok(MappedFile::from_mmap(mmap))
+}
+
+impl MappedFile {
+    /// Takes a mapping made with other options, such as an offset into the
+    /// file or `populate`.
+    #[must_use]
+    pub fn from_mmap(mmap: Mmap) -> Self {
+        MappedFile { mmap }
+    }
+
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Deserializes a single value written by [`serialize`](crate::serialize).
+    pub fn deserialize<'de, T>(&'de self) -> Result<T>
+    where
+        T: Deserialize<'de>,
+    {
+        crate::deserialize(&self.mmap)
+    }
+
+    /// Iterates over the records in the file, which were written back to back
+    /// by [`serialize`](crate::serialize). Call
+    /// [`framed`](StreamDeserializer::framed) on the iterator for records
+    /// written by [`serialize_framed`](crate::serialize_framed).
+    #[must_use]
+    pub fn records<'de, T>(&'de self) -> StreamDeserializer<'de, SliceRead<'de>, T>
+    where
+        T: Deserialize<'de>,
+    {
+        StreamDeserializer::from_slice(&self.mmap)
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> Mmap {
+        self.mmap
+    }
+}
@@ -0,0 +1,257 @@
+//! Input sources that records can be deserialized from.
+
+use crate::de::utf8_char_width;
+use crate::{Error, Result};
+use byteorder::ReadBytesExt;
+use std::io::{self, Read as _};
+use std::{mem, str};
+
+/// A source of serde-bench encoded bytes, either a slice that deserialized
+/// values may borrow from or an `io::Read` that is copied out of.
+///
+/// This trait is sealed and cannot be implemented outside of serde-bench.
+pub trait Read<'de>: private::Sealed + io::Read {
+    #[doc(hidden)]
+    fn read_bytes(&mut self, len: usize) -> Result<Reference<'de, '_>>;
+
+    #[doc(hidden)]
+    fn read_char(&mut self) -> Result<char>;
+
+    #[doc(hidden)]
+    fn at_end(&mut self) -> Result<bool>;
+
+    #[doc(hidden)]
+    #[inline]
+    fn validate_utf8(&self) -> bool {
+        true
+    }
+}
+
+#[doc(hidden)]
+pub enum Reference<'b, 'c> {
+    Borrowed(&'b [u8]),
+    Copied(&'c [u8]),
+}
+
+pub(crate) mod private {
+    pub trait Sealed {}
+}
+
+pub struct SliceRead<'a> {
+    slice: &'a [u8],
+    validate_utf8: bool,
+}
+
+impl<'a> SliceRead<'a> {
+    #[must_use]
+    pub fn new(slice: &'a [u8]) -> Self {
+        SliceRead {
+            slice,
+            validate_utf8: true,
+        }
+    }
+
+    /// Borrows strings without checking that they are UTF-8. Every read is
+    /// still bounds checked.
+    ///
+    /// # Safety
+    ///
+    /// Every string deserialized from this reader must be valid UTF-8.
+    #[must_use]
+    pub unsafe fn skip_utf8_validation(mut self) -> Self {
+        self.validate_utf8 = false;
+        self
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.slice.len()
+    }
+
+    #[inline]
+    pub(crate) fn read_slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.slice.len() {
+            return Err(Error::new("unexpected end of input"));
+        }
+        let (bytes, rest) = self.slice.split_at(len);
+        self.slice = rest;
+        Ok(bytes)
+    }
+}
+
+impl<'a> private::Sealed for SliceRead<'a> {}
+
+impl<'a> io::Read for SliceRead<'a> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.slice.read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.slice.read_exact(buf)
+    }
+}
+
+impl<'a> Read<'a> for SliceRead<'a> {
+    #[inline]
+    fn read_bytes(&mut self, len: usize) -> Result<Reference<'a, '_>> {
+        self.read_slice(len).map(Reference::Borrowed)
+    }
+
+    #[inline]
+    fn read_char(&mut self) -> Result<char> {
+        let Some(&first) = self.slice.first() else {
+            return Err(Error::new("unexpected end of input"));
+        };
+        let width = utf8_char_width(first);
+        if width == 1 {
+            self.slice = &self.slice[1..];
+            return Ok(first as char);
+        }
+        if width == 0 {
+            return Err(Error::new("invalid char"));
+        }
+        if width > self.slice.len() {
+            return Err(Error::new("unexpected end of input"));
+        }
+        let res = match str::from_utf8(&self.slice[..width]) {
+            Ok(s) => s.chars().next().unwrap(),
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+        self.slice = &self.slice[width..];
+        Ok(res)
+    }
+
+    #[inline]
+    fn at_end(&mut self) -> Result<bool> {
+        Ok(self.slice.is_empty())
+    }
+
+    #[inline]
+    fn validate_utf8(&self) -> bool {
+        self.validate_utf8
+    }
+}
+
+pub struct IoRead<R> {
+    reader: R,
+    // Byte read ahead by `at_end`, returned by the next read.
+    peeked: Option<u8>,
+    // Holds strings and byte arrays, which cannot be borrowed from a reader.
+    scratch: Vec<u8>,
+}
+
+impl<R> IoRead<R>
+where
+    R: io::Read,
+{
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        IoRead {
+            reader,
+            peeked: None,
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl<R> private::Sealed for IoRead<R> {}
+
+impl<R> io::Read for IoRead<R>
+where
+    R: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match (self.peeked, buf.split_first_mut()) {
+            (Some(byte), Some((first, _))) => {
+                *first = byte;
+                self.peeked = None;
+                Ok(1)
+            }
+            _ => self.reader.read(buf),
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        match (self.peeked, buf.split_first_mut()) {
+            (Some(byte), Some((first, rest))) => {
+                *first = byte;
+                self.peeked = None;
+                self.reader.read_exact(rest)
+            }
+            _ => self.reader.read_exact(buf),
+        }
+    }
+}
+
+impl<'de, R> Read<'de> for IoRead<R>
+where
+    R: io::Read,
+{
+    fn read_bytes(&mut self, len: usize) -> Result<Reference<'de, '_>> {
+        let mut scratch = mem::take(&mut self.scratch);
+        scratch.clear();
+        // Grows with the bytes actually read rather than trusting `len`.
+        let result = (&mut *self).take(len as u64).read_to_end(&mut scratch);
+        self.scratch = scratch;
+        result?;
+        if self.scratch.len() < len {
+            return Err(Error::new("unexpected end of input"));
+        }
+        Ok(Reference::Copied(&self.scratch))
+    }
+
+    fn read_char(&mut self) -> Result<char> {
+        read_char(self)
+    }
+
+    fn at_end(&mut self) -> Result<bool> {
+        if self.peeked.is_some() {
+            return Ok(false);
+        }
+        let mut byte = 0;
+        loop {
+            match self.reader.read(std::slice::from_mut(&mut byte)) {
+                Ok(0) => return Ok(true),
+                Ok(_) => {
+                    self.peeked = Some(byte);
+                    return Ok(false);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn from_utf8(bytes: &[u8]) -> Result<&str> {
+    #[cfg(feature = "simd")]
+    {
+        if let Ok(string) = simdutf8::basic::from_utf8(bytes) {
+            return Ok(string);
+        }
+    }
+    // Also reached on invalid input with `simd`, to report where the error is.
+    str::from_utf8(bytes).map_err(Error::from)
+}
+
+// Decodes a char from a reader that cannot be looked ahead into.
+pub(crate) fn read_char<R: io::Read>(reader: &mut R) -> Result<char> {
+    let first = reader.read_u8()?;
+    let width = utf8_char_width(first);
+    if width == 1 {
+        return Ok(first as char);
+    }
+    if width == 0 {
+        return Err(Error::new("invalid char"));
+    }
+    let mut buf = [first, 0, 0, 0];
+    reader.read_exact(&mut buf[1..width])?;
+    match str::from_utf8(&buf[..width]) {
+        Ok(s) => Ok(s.chars().next().unwrap()),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -1,3 +1,5 @@
+use crate::bulk::{LenSerializer, RawSerializer, LEN, RAW};
+use crate::config::Config;
 use crate::{Error, Result};
 use byteorder::{NativeEndian, WriteBytesExt};
 use serde::ser::{
@@ -8,6 +10,7 @@ use std::io::Write;
 
 pub struct Serializer<W> {
     writer: W,
+    config: Config,
 }
 
 impl<W> Serializer<W>
@@ -15,7 +18,22 @@ where
     W: Write,
 {
     pub fn new(w: W) -> Self {
-        Serializer { writer: w }
+        Serializer::with_config(w, Config::new())
+    }
+
+    pub fn with_config(w: W, config: Config) -> Self {
+        Serializer { writer: w, config }
+    }
+
+    #[inline]
+    fn write_len(&mut self, len: usize) -> Result<()> {
+        self.config.write_len(&mut self.writer, len as u64)
+    }
+
+    #[inline]
+    fn write_variant_index(&mut self, variant_index: u32) -> Result<()> {
+        self.config
+            .write_variant_index(&mut self.writer, variant_index)
     }
 }
 
@@ -115,7 +133,7 @@ where
 
     #[inline]
     fn serialize_str(self, v: &str) -> Result<()> {
-        self.serialize_u64(v.len() as u64)?;
+        self.write_len(v.len())?;
         self.writer.write_all(v.as_bytes()).map_err(From::from)
     }
 
@@ -128,7 +146,7 @@ where
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        self.serialize_u64(v.len() as u64)?;
+        self.write_len(v.len())?;
         self.writer.write_all(v).map_err(From::from)
     }
 
@@ -149,7 +167,7 @@ where
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
         let len = len.expect("do not know how to serialize a sequence with no length");
-        self.serialize_u64(len as u64)?;
+        self.write_len(len)?;
         Ok(self)
     }
 
@@ -175,14 +193,14 @@ where
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.serialize_u32(variant_index)?;
+        self.write_variant_index(variant_index)?;
         Ok(self)
     }
 
     #[inline]
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
         let len = len.expect("do not know how to serialize a map with no length");
-        self.serialize_u64(len as u64)?;
+        self.write_len(len)?;
         Ok(self)
     }
 
@@ -199,15 +217,24 @@ where
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.serialize_u32(variant_index)?;
+        self.write_variant_index(variant_index)?;
         Ok(self)
     }
 
     #[inline]
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + serde::ser::Serialize,
     {
+        if name == RAW {
+            return value.serialize(RawSerializer {
+                writer: &mut self.writer,
+            });
+        }
+        if name == LEN {
+            let len = value.serialize(LenSerializer)?;
+            return self.config.write_len(&mut self.writer, len);
+        }
         value.serialize(self)
     }
 
@@ -222,7 +249,7 @@ where
     where
         T: ?Sized + serde::ser::Serialize,
     {
-        self.serialize_u32(variant_index)?;
+        self.write_variant_index(variant_index)?;
         value.serialize(self)
     }
 
@@ -233,7 +260,7 @@ where
         variant_index: u32,
         _variant: &'static str,
     ) -> Result<()> {
-        self.serialize_u32(variant_index)
+        self.write_variant_index(variant_index)
     }
 
     fn is_human_readable(&self) -> bool {
@@ -0,0 +1,178 @@
+use crate::de::Deserializer;
+use crate::read::{IoRead, Read, Reference, SliceRead};
+use crate::{Error, Result};
+use serde::de::{Deserialize, DeserializeOwned};
+use std::io;
+use std::marker::PhantomData;
+
+/// Iterator over records written back to back, each by
+/// [`serialize`](crate::serialize) or, in framed mode, by
+/// [`serialize_framed`](crate::serialize_framed).
+///
+/// Without framing, iteration ends after the first error because the start of
+/// the next record is unknown. With framing, a record that fails to
+/// deserialize is reported and iteration continues with the next frame.
+///
+/// Records are decoded with the default [`Config`](crate::Config).
+pub struct StreamDeserializer<'de, R, T> {
+    de: Deserializer<R>,
+    framed: bool,
+    failed: bool,
+    output: PhantomData<T>,
+    lifetime: PhantomData<&'de ()>,
+}
+
+impl<'de, T> StreamDeserializer<'de, SliceRead<'de>, T>
+where
+    T: Deserialize<'de>,
+{
+    #[must_use]
+    pub fn from_slice(bytes: &'de [u8]) -> Self {
+        StreamDeserializer::new(SliceRead::new(bytes))
+    }
+}
+
+impl<'de, R, T> StreamDeserializer<'de, IoRead<R>, T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    #[must_use]
+    pub fn from_reader(reader: R) -> Self {
+        StreamDeserializer::new(IoRead::new(reader))
+    }
+}
+
+impl<'de, R, T> StreamDeserializer<'de, R, T>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+{
+    #[must_use]
+    pub fn new(read: R) -> Self {
+        StreamDeserializer {
+            de: Deserializer::new(read),
+            framed: false,
+            failed: false,
+            output: PhantomData,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Expects every record to be preceded by its length as a u64, as written
+    /// by [`serialize_framed`](crate::serialize_framed) or by a
+    /// [`FrameWriter`](crate::FrameWriter) with the default
+    /// [`FrameConfig`](crate::FrameConfig). Frames with another length prefix
+    /// or a checksum need a [`FrameReader`](crate::FrameReader).
+    #[must_use]
+    pub fn framed(mut self) -> Self {
+        self.framed = true;
+        self
+    }
+
+    fn next_framed(&mut self) -> Result<T> {
+        let len = Deserialize::deserialize(&mut self.de).map_err(|err| self.fail(err))?;
+        let mut frame = Deserializer::new(Frame {
+            read: &mut self.de.read,
+            remaining: len,
+        });
+        let result = T::deserialize(&mut frame);
+
+        // Skip whatever the record left unread so that the next frame starts
+        // in the right place.
+        let frame = frame.read;
+        let remaining = frame.remaining;
+        if remaining > 0 {
+            if let Err(err) = frame.read.read_bytes(remaining) {
+                return Err(self.fail(err));
+            }
+        }
+
+        match result {
+            Ok(_) if remaining > 0 => Err(Error::new("trailing bytes in frame")),
+            result => result,
+        }
+    }
+
+    fn fail(&mut self, err: Error) -> Error {
+        self.failed = true;
+        err
+    }
+}
+
+impl<'de, R, T> Iterator for StreamDeserializer<'de, R, T>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.failed {
+            return None;
+        }
+        match self.de.read.at_end() {
+            Ok(true) => return None,
+            Ok(false) => {}
+            Err(err) => return Some(Err(self.fail(err))),
+        }
+        if self.framed {
+            Some(self.next_framed())
+        } else {
+            Some(T::deserialize(&mut self.de).map_err(|err| self.fail(err)))
+        }
+    }
+}
+
+// Limits reads to the bytes of a single frame.
+struct Frame<'a, R> {
+    read: &'a mut R,
+    remaining: usize,
+}
+
+impl<'a, R> crate::read::private::Sealed for Frame<'a, R> {}
+
+impl<'a, R> io::Read for Frame<'a, R>
+where
+    R: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.remaining);
+        let n = self.read.read(&mut buf[..len])?;
+        self.remaining -= n;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if buf.len() > self.remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "unexpected end of frame",
+            ));
+        }
+        self.read.read_exact(buf)?;
+        self.remaining -= buf.len();
+        Ok(())
+    }
+}
+
+impl<'de, 'a, R> Read<'de> for Frame<'a, R>
+where
+    R: Read<'de>,
+{
+    fn read_bytes(&mut self, len: usize) -> Result<Reference<'de, '_>> {
+        if len > self.remaining {
+            return Err(Error::new("unexpected end of frame"));
+        }
+        self.remaining -= len;
+        self.read.read_bytes(len)
+    }
+
+    fn read_char(&mut self) -> Result<char> {
+        crate::read::read_char(self)
+    }
+
+    fn at_end(&mut self) -> Result<bool> {
+        Ok(self.remaining == 0)
+    }
+}
@@ -0,0 +1,394 @@
+use crate::bulk::{LEN, RAW};
+use crate::token::Token;
+use crate::{Error, Result};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+
+pub struct Deserializer<'de> {
+    tokens: &'de [Token],
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(tokens: &'de [Token]) -> Self {
+        Deserializer { tokens }
+    }
+
+    pub fn remaining(&self) -> &'de [Token] {
+        self.tokens
+    }
+
+    fn next_token(&mut self) -> Result<Token> {
+        let Some((&first, rest)) = self.tokens.split_first() else {
+            return Err(Error::new("ran out of tokens to deserialize"));
+        };
+        self.tokens = rest;
+        Ok(first)
+    }
+
+    fn expect_token(&mut self, expected: Token) -> Result<()> {
+        let token = self.next_token()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(unexpected(token, &format!("Token::{expected:?}")))
+        }
+    }
+
+    fn visit_compound<V>(&mut self, len: usize, end: Token, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let value = visitor.visit_seq(Compound {
+            de: self,
+            remaining: len,
+        })?;
+        self.expect_token(end)?;
+        Ok(value)
+    }
+}
+
+macro_rules! impl_scalars {
+    ($($dser_method:ident($token:ident) => $visitor_method:ident,)*) => {
+        $(
+            fn $dser_method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                match self.next_token()? {
+                    Token::$token(v) => visitor.$visitor_method(v),
+                    token => Err(unexpected(token, concat!("Token::", stringify!($token)))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_any` is not supported"))
+    }
+
+    impl_scalars! {
+        deserialize_bool(Bool) => visit_bool,
+        deserialize_i8(I8) => visit_i8,
+        deserialize_i16(I16) => visit_i16,
+        deserialize_i32(I32) => visit_i32,
+        deserialize_i64(I64) => visit_i64,
+        deserialize_i128(I128) => visit_i128,
+        deserialize_u8(U8) => visit_u8,
+        deserialize_u16(U16) => visit_u16,
+        deserialize_u32(U32) => visit_u32,
+        deserialize_u64(U64) => visit_u64,
+        deserialize_u128(U128) => visit_u128,
+        deserialize_f32(F32) => visit_f32,
+        deserialize_f64(F64) => visit_f64,
+        deserialize_char(Char) => visit_char,
+        deserialize_str(Str) => visit_borrowed_str,
+        deserialize_string(Str) => visit_borrowed_str,
+        deserialize_bytes(Bytes) => visit_borrowed_bytes,
+        deserialize_byte_buf(Bytes) => visit_borrowed_bytes,
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.next_token()? {
+            Token::None => visitor.visit_none(),
+            Token::Some => visitor.visit_some(self),
+            token => Err(unexpected(token, "Token::None or Token::Some")),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.expect_token(Token::Unit)?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.expect_token(Token::UnitStruct { name })?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == LEN {
+            return match self.next_token()? {
+                Token::Len(v) => visitor.visit_u64(v),
+                token => Err(unexpected(token, "Token::Len")),
+            };
+        }
+        if name == RAW {
+            return match self.next_token()? {
+                Token::Raw(v) => visitor.visit_borrowed_bytes(v),
+                token => Err(unexpected(token, "Token::Raw")),
+            };
+        }
+        self.expect_token(Token::NewtypeStruct { name })?;
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.next_token()? {
+            Token::Seq { len } => self.visit_compound(len, Token::SeqEnd, visitor),
+            token => Err(unexpected(token, "Token::Seq")),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.expect_token(Token::Tuple { len })?;
+        self.visit_compound(len, Token::TupleEnd, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.expect_token(Token::TupleStruct { name, len })?;
+        self.visit_compound(len, Token::TupleStructEnd, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let len = match self.next_token()? {
+            Token::Map { len } => len,
+            token => return Err(unexpected(token, "Token::Map")),
+        };
+        let value = visitor.visit_map(Compound {
+            de: self,
+            remaining: len,
+        })?;
+        self.expect_token(Token::MapEnd)?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.next_token()? {
+            Token::Struct { name: n, len } if n == name => {
+                self.visit_compound(len, Token::StructEnd, visitor)
+            }
+            token => Err(unexpected(
+                token,
+                &format!("Token::Struct {{ name: {name:?}, .. }}"),
+            )),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let token = self.next_token()?;
+        match token {
+            Token::UnitVariant {
+                name: n,
+                variant_index,
+            }
+            | Token::NewtypeVariant {
+                name: n,
+                variant_index,
+            }
+            | Token::TupleVariant {
+                name: n,
+                variant_index,
+                ..
+            }
+            | Token::StructVariant {
+                name: n,
+                variant_index,
+                ..
+            } if n == name => visitor.visit_enum(Enum {
+                de: self,
+                token,
+                variant_index,
+            }),
+            _ => Err(unexpected(token, &format!("a variant of enum {name:?}"))),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_identifier` is not supported"))
+    }
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_ignored_any` is not supported"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+fn unexpected(token: Token, expected: &str) -> Error {
+    Error::new(format!("expected {expected}, found Token::{token:?}"))
+}
+
+// For seqs, tuples, structs and maps, which all hand out exactly the number
+// of elements announced by their opening token.
+struct Compound<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de, 'a> SeqAccess<'de> for Compound<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            seed.deserialize(&mut *self.de).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for Compound<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            seed.deserialize(&mut *self.de).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct Enum<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    token: Token,
+    variant_index: u32,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let deserializer = <u32 as IntoDeserializer<Error>>::into_deserializer(self.variant_index);
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for Enum<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.token {
+            Token::UnitVariant { .. } => Ok(()),
+            token => Err(unexpected(token, "Token::UnitVariant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.token {
+            Token::NewtypeVariant { .. } => seed.deserialize(self.de),
+            token => Err(unexpected(token, "Token::NewtypeVariant")),
+        }
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.token {
+            Token::TupleVariant { len: n, .. } if n == len => {
+                self.de.visit_compound(len, Token::TupleVariantEnd, visitor)
+            }
+            token => Err(unexpected(
+                token,
+                &format!("Token::TupleVariant {{ len: {len}, .. }}"),
+            )),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.token {
+            Token::StructVariant { len, .. } => {
+                self.de
+                    .visit_compound(len, Token::StructVariantEnd, visitor)
+            }
+            token => Err(unexpected(token, "Token::StructVariant")),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Token-level testing of `Serialize` and `Deserialize` impls against the
+//! serde-bench format, in the style of `serde_test`.
+//!
+//! Tokens describe values the way this format sees them: struct field names
+//! and variant names are not part of the stream because they are never
+//! encoded, and enum variants are identified by their index.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! use serde_bench::token::{assert_tokens, Token};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! assert_tokens(
+//!     &Point { x: 1, y: -1 },
+//!     &[
+//!         Token::Struct { name: "Point", len: 2 },
+//!         Token::I32(1),
+//!         Token::I32(-1),
+//!         Token::StructEnd,
+//!     ],
+//! );
+//! ```
+
+#![allow(clippy::missing_panics_doc)]
+
+mod de;
+mod replay;
+mod ser;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Token {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(&'static str),
+    Bytes(&'static [u8]),
+    /// The length of a [`bulk`](crate::bulk) slice, which comes before its
+    /// `Raw` contents.
+    Len(u64),
+    /// The contents of a [`bulk`](crate::bulk) slice or array, which are
+    /// encoded without a length prefix.
+    Raw(&'static [u8]),
+
+    None,
+    /// Followed by the value.
+    Some,
+
+    Unit,
+    UnitStruct {
+        name: &'static str,
+    },
+    UnitVariant {
+        name: &'static str,
+        variant_index: u32,
+    },
+
+    /// Followed by the value.
+    NewtypeStruct {
+        name: &'static str,
+    },
+    /// Followed by the value.
+    NewtypeVariant {
+        name: &'static str,
+        variant_index: u32,
+    },
+
+    /// Followed by `len` elements and `SeqEnd`.
+    Seq {
+        len: usize,
+    },
+    SeqEnd,
+
+    /// Followed by `len` elements and `TupleEnd`.
+    Tuple {
+        len: usize,
+    },
+    TupleEnd,
+
+    /// Followed by `len` fields and `TupleStructEnd`.
+    TupleStruct {
+        name: &'static str,
+        len: usize,
+    },
+    TupleStructEnd,
+
+    /// Followed by `len` fields and `TupleVariantEnd`.
+    TupleVariant {
+        name: &'static str,
+        variant_index: u32,
+        len: usize,
+    },
+    TupleVariantEnd,
+
+    /// Followed by `len` alternating keys and values, and `MapEnd`.
+    Map {
+        len: usize,
+    },
+    MapEnd,
+
+    /// Followed by the values of `len` fields and `StructEnd`.
+    Struct {
+        name: &'static str,
+        len: usize,
+    },
+    StructEnd,
+
+    /// Followed by the values of `len` fields and `StructVariantEnd`.
+    StructVariant {
+        name: &'static str,
+        variant_index: u32,
+        len: usize,
+    },
+    StructVariantEnd,
+}
+
+/// Asserts that `value` serializes to `tokens` and that `tokens` deserialize
+/// back to `value`.
+#[track_caller]
+pub fn assert_tokens<T>(value: &T, tokens: &[Token])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    assert_ser_tokens(value, tokens);
+    assert_de_tokens(value, tokens);
+}
+
+/// Asserts that `value` serializes to `tokens`, and that the bytes written by
+/// `serde_bench::serialize` are exactly the encoding of `tokens`.
+#[track_caller]
+pub fn assert_ser_tokens<T>(value: &T, tokens: &[Token])
+where
+    T: Serialize,
+{
+    let mut ser = ser::Serializer::new(tokens);
+    if let Err(err) = value.serialize(&mut ser) {
+        panic!("value failed to serialize: {err}");
+    }
+    if let Some(token) = ser.remaining().first() {
+        panic!(
+            "{} remaining tokens, starting with {:?}",
+            ser.remaining().len(),
+            token
+        );
+    }
+
+    let mut expected = Vec::new();
+    if let Err(err) = replay::serialize(&mut expected, tokens) {
+        panic!("tokens failed to encode: {err}");
+    }
+    let mut actual = Vec::new();
+    crate::serialize(&mut actual, value).unwrap();
+    assert_eq!(actual, expected, "encoded bytes differ from the tokens");
+}
+
+/// Asserts that `tokens` deserialize to `value`, and that
+/// `serde_bench::deserialize` of the encoding of `tokens` gives `value` too.
+///
+/// Types that borrow from the input can't outlive the encoded bytes; check
+/// those with [`assert_de_tokens_borrowed`].
+#[track_caller]
+pub fn assert_de_tokens<T>(value: &T, tokens: &[Token])
+where
+    T: DeserializeOwned + PartialEq + Debug,
+{
+    assert_de_tokens_borrowed(value, tokens);
+
+    let mut bytes = Vec::new();
+    if let Err(err) = replay::serialize(&mut bytes, tokens) {
+        panic!("tokens failed to encode: {err}");
+    }
+    let decoded = match crate::deserialize::<T>(&bytes) {
+        Ok(decoded) => decoded,
+        Err(err) => panic!("encoded tokens failed to deserialize: {err}"),
+    };
+    assert_eq!(decoded, *value, "decoded bytes differ from the tokens");
+}
+
+/// Asserts that `tokens` deserialize to `value`, which may borrow from the
+/// tokens. Unlike [`assert_de_tokens`], the encoded bytes are not decoded.
+#[track_caller]
+pub fn assert_de_tokens_borrowed<'de, T>(value: &T, tokens: &'de [Token])
+where
+    T: Deserialize<'de> + PartialEq + Debug,
+{
+    let mut de = de::Deserializer::new(tokens);
+    let deserialized = match T::deserialize(&mut de) {
+        Ok(deserialized) => deserialized,
+        Err(err) => panic!("tokens failed to deserialize: {err}"),
+    };
+    if let Some(token) = de.remaining().first() {
+        panic!(
+            "{} remaining tokens, starting with {:?}",
+            de.remaining().len(),
+            token
+        );
+    }
+    assert_eq!(deserialized, *value);
+}
+
+/// Asserts that serializing `value` fails with the message `error`.
+#[track_caller]
+pub fn assert_ser_tokens_error<T>(value: &T, tokens: &[Token], error: &str)
+where
+    T: Serialize,
+{
+    let mut ser = ser::Serializer::new(tokens);
+    match value.serialize(&mut ser) {
+        Ok(()) => panic!("value serialized successfully"),
+        Err(err) => assert_eq!(err.to_string(), error),
+    }
+}
+
+/// Asserts that deserializing `tokens` fails with the message `error`.
+#[track_caller]
+pub fn assert_de_tokens_error<'de, T>(tokens: &'de [Token], error: &str)
+where
+    T: Deserialize<'de> + Debug,
+{
+    let mut de = de::Deserializer::new(tokens);
+    match T::deserialize(&mut de) {
+        Ok(value) => panic!("tokens deserialized successfully as {value:?}"),
+        Err(err) => assert_eq!(err.to_string(), error),
+    }
+}
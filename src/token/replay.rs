@@ -0,0 +1,157 @@
+// Drives the real serde-bench Serializer from a token stream, giving the
+// bytes that a type serializing as those tokens must produce.
+
+use crate::bulk::{Len, Raw};
+use crate::token::Token;
+use crate::{Error, Result};
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
+};
+use std::cell::Cell;
+
+pub fn serialize(out: &mut Vec<u8>, tokens: &[Token]) -> Result<()> {
+    let replay = Replay {
+        tokens: Cell::new(tokens),
+    };
+    crate::serialize(out, &replay)?;
+    match replay.tokens.get().first() {
+        None => Ok(()),
+        Some(token) => Err(Error::new(format!(
+            "expected end of tokens, found Token::{token:?}",
+        ))),
+    }
+}
+
+struct Replay<'a> {
+    tokens: Cell<&'a [Token]>,
+}
+
+impl<'a> Replay<'a> {
+    fn next_token<E: ser::Error>(&self) -> std::result::Result<Token, E> {
+        let Some((&first, rest)) = self.tokens.get().split_first() else {
+            return Err(E::custom("ran out of tokens to encode"));
+        };
+        self.tokens.set(rest);
+        Ok(first)
+    }
+
+    // Consumes `end` and returns true if it is the next token.
+    fn at_end(&self, end: Token) -> bool {
+        match self.tokens.get().split_first() {
+            Some((&first, rest)) if first == end => {
+                self.tokens.set(rest);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a> Serialize for Replay<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self.next_token()? {
+            Token::Bool(v) => serializer.serialize_bool(v),
+            Token::I8(v) => serializer.serialize_i8(v),
+            Token::I16(v) => serializer.serialize_i16(v),
+            Token::I32(v) => serializer.serialize_i32(v),
+            Token::I64(v) => serializer.serialize_i64(v),
+            Token::I128(v) => serializer.serialize_i128(v),
+            Token::U8(v) => serializer.serialize_u8(v),
+            Token::U16(v) => serializer.serialize_u16(v),
+            Token::U32(v) => serializer.serialize_u32(v),
+            Token::U64(v) => serializer.serialize_u64(v),
+            Token::U128(v) => serializer.serialize_u128(v),
+            Token::F32(v) => serializer.serialize_f32(v),
+            Token::F64(v) => serializer.serialize_f64(v),
+            Token::Char(v) => serializer.serialize_char(v),
+            Token::Str(v) => serializer.serialize_str(v),
+            Token::Bytes(v) => serializer.serialize_bytes(v),
+            Token::Len(v) => Len(v).serialize(serializer),
+            Token::Raw(v) => Raw(v).serialize(serializer),
+            Token::None => serializer.serialize_none(),
+            Token::Some => serializer.serialize_some(self),
+            Token::Unit => serializer.serialize_unit(),
+            Token::UnitStruct { name } => serializer.serialize_unit_struct(name),
+            Token::UnitVariant {
+                name,
+                variant_index,
+            } => serializer.serialize_unit_variant(name, variant_index, ""),
+            Token::NewtypeStruct { name } => serializer.serialize_newtype_struct(name, self),
+            Token::NewtypeVariant {
+                name,
+                variant_index,
+            } => serializer.serialize_newtype_variant(name, variant_index, "", self),
+            Token::Seq { len } => {
+                let mut seq = serializer.serialize_seq(Some(len))?;
+                while !self.at_end(Token::SeqEnd) {
+                    seq.serialize_element(self)?;
+                }
+                seq.end()
+            }
+            Token::Tuple { len } => {
+                let mut tuple = serializer.serialize_tuple(len)?;
+                while !self.at_end(Token::TupleEnd) {
+                    tuple.serialize_element(self)?;
+                }
+                tuple.end()
+            }
+            Token::TupleStruct { name, len } => {
+                let mut tuple = serializer.serialize_tuple_struct(name, len)?;
+                while !self.at_end(Token::TupleStructEnd) {
+                    tuple.serialize_field(self)?;
+                }
+                tuple.end()
+            }
+            Token::TupleVariant {
+                name,
+                variant_index,
+                len,
+            } => {
+                let mut tuple = serializer.serialize_tuple_variant(name, variant_index, "", len)?;
+                while !self.at_end(Token::TupleVariantEnd) {
+                    tuple.serialize_field(self)?;
+                }
+                tuple.end()
+            }
+            Token::Map { len } => {
+                let mut map = serializer.serialize_map(Some(len))?;
+                while !self.at_end(Token::MapEnd) {
+                    map.serialize_key(self)?;
+                    map.serialize_value(self)?;
+                }
+                map.end()
+            }
+            Token::Struct { name, len } => {
+                let mut st = serializer.serialize_struct(name, len)?;
+                while !self.at_end(Token::StructEnd) {
+                    st.serialize_field("", self)?;
+                }
+                st.end()
+            }
+            Token::StructVariant {
+                name,
+                variant_index,
+                len,
+            } => {
+                let mut st = serializer.serialize_struct_variant(name, variant_index, "", len)?;
+                while !self.at_end(Token::StructVariantEnd) {
+                    st.serialize_field("", self)?;
+                }
+                st.end()
+            }
+            token @ (Token::SeqEnd
+            | Token::TupleEnd
+            | Token::TupleStructEnd
+            | Token::TupleVariantEnd
+            | Token::MapEnd
+            | Token::StructEnd
+            | Token::StructVariantEnd) => Err(ser::Error::custom(format_args!(
+                "unexpected Token::{token:?}",
+            ))),
+        }
+    }
+}
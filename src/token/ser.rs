@@ -0,0 +1,387 @@
+use crate::bulk::{LenSerializer, RawSerializer, LEN, RAW};
+use crate::token::Token;
+use crate::{Error, Result};
+use serde::ser::{self, Serialize};
+
+pub struct Serializer<'a> {
+    tokens: &'a [Token],
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(tokens: &'a [Token]) -> Self {
+        Serializer { tokens }
+    }
+
+    pub fn remaining(&self) -> &'a [Token] {
+        self.tokens
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let (&first, rest) = self.tokens.split_first()?;
+        self.tokens = rest;
+        Some(first)
+    }
+
+    fn assert_next_token(&mut self, expected: Token) -> Result<()> {
+        match self.next_token() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(Error::new(format!(
+                "expected Token::{token:?} but serialized as {expected:?}",
+            ))),
+            None => Err(Error::new(format!(
+                "expected end of tokens, but {expected:?} was serialized",
+            ))),
+        }
+    }
+}
+
+impl<'s, 'a> ser::Serializer for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.assert_next_token(Token::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.assert_next_token(Token::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.assert_next_token(Token::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.assert_next_token(Token::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.assert_next_token(Token::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.assert_next_token(Token::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.assert_next_token(Token::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.assert_next_token(Token::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.assert_next_token(Token::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.assert_next_token(Token::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.assert_next_token(Token::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.assert_next_token(Token::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.assert_next_token(Token::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.assert_next_token(Token::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        match self.next_token() {
+            Some(Token::Str(s)) if s == v => Ok(()),
+            Some(token) => Err(Error::new(format!(
+                "expected Token::{token:?} but serialized as Str({v:?})",
+            ))),
+            None => Err(Error::new(format!(
+                "expected end of tokens, but Str({v:?}) was serialized",
+            ))),
+        }
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        match self.next_token() {
+            Some(Token::Bytes(b)) if b == v => Ok(()),
+            Some(token) => Err(Error::new(format!(
+                "expected Token::{token:?} but serialized as Bytes({v:?})",
+            ))),
+            None => Err(Error::new(format!(
+                "expected end of tokens, but Bytes({v:?}) was serialized",
+            ))),
+        }
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.assert_next_token(Token::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.assert_next_token(Token::Some)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.assert_next_token(Token::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        self.assert_next_token(Token::UnitStruct { name })
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        self.assert_next_token(Token::UnitVariant {
+            name,
+            variant_index,
+        })
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if name == LEN {
+            let v = value.serialize(LenSerializer)?;
+            return self.assert_next_token(Token::Len(v));
+        }
+        if name == RAW {
+            let mut v = Vec::new();
+            value.serialize(RawSerializer { writer: &mut v })?;
+            return match self.next_token() {
+                Some(Token::Raw(b)) if b == v => Ok(()),
+                Some(token) => Err(Error::new(format!(
+                    "expected Token::{token:?} but serialized as Raw({v:?})",
+                ))),
+                None => Err(Error::new(format!(
+                    "expected end of tokens, but Raw({v:?}) was serialized",
+                ))),
+            };
+        }
+        self.assert_next_token(Token::NewtypeStruct { name })?;
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.assert_next_token(Token::NewtypeVariant {
+            name,
+            variant_index,
+        })?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        let Some(len) = len else {
+            return Err(Error::new("sequences must have a known length"));
+        };
+        self.assert_next_token(Token::Seq { len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self> {
+        self.assert_next_token(Token::Tuple { len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Self> {
+        self.assert_next_token(Token::TupleStruct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self> {
+        self.assert_next_token(Token::TupleVariant {
+            name,
+            variant_index,
+            len,
+        })?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        let Some(len) = len else {
+            return Err(Error::new("maps must have a known length"));
+        };
+        self.assert_next_token(Token::Map { len })?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self> {
+        self.assert_next_token(Token::Struct { name, len })?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self> {
+        self.assert_next_token(Token::StructVariant {
+            name,
+            variant_index,
+            len,
+        })?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<'s, 'a> ser::SerializeSeq for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::SeqEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeTuple for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::TupleEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeTupleStruct for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::TupleStructEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeTupleVariant for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::TupleVariantEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeMap for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::MapEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeStruct for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::StructEnd)
+    }
+}
+
+impl<'s, 'a> ser::SerializeStructVariant for &'s mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.assert_next_token(Token::StructVariantEnd)
+    }
+}
@@ -0,0 +1,759 @@
+use crate::bulk::{Len, Raw, LEN, RAW};
+use crate::de::Deserializer;
+use crate::read::SliceRead;
+use crate::{Error, Result};
+use serde::de::{
+    self, Deserialize, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::ser::{self, Serialize, SerializeMap, Serializer};
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Decodes serde-bench `bytes` as a `T` and feeds the decoded values straight
+/// into `serializer`, for example to view a captured benchmark input as JSON.
+///
+/// `T`'s `Deserialize` impl supplies what the bytes alone do not: the width
+/// of each number and the names of fields and variants. Every value is passed
+/// to `serializer` as soon as it is decoded, so output follows the layout of
+/// `T` without a `T` having to be built first or implement `Serialize`.
+///
+/// Both directions use the default [`Config`](crate::Config).
+///
+/// ```
+/// # use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let mut bytes = Vec::new();
+/// serde_bench::serialize(&mut bytes, &(1i32, -1i32)).unwrap();
+///
+/// let mut json = Vec::new();
+/// let mut serializer = serde_json::Serializer::new(&mut json);
+/// serde_bench::transcode_to::<Point, _>(&bytes, &mut serializer).unwrap();
+/// assert_eq!(json, br#"{"x":1,"y":-1}"#);
+/// ```
+pub fn transcode_to<'de, T, S>(bytes: &'de [u8], serializer: S) -> Result<S::Ok>
+where
+    T: Deserialize<'de>,
+    S: Serializer,
+{
+    let mut de = Deserializer::new(SliceRead::new(bytes));
+    let mut ok = None;
+    PhantomData::<T>.deserialize(Transcoder {
+        de: &mut de,
+        ser: serializer,
+        ok: &mut ok,
+    })?;
+    ok.ok_or_else(skipped)
+}
+
+/// Deserializes a `T` from another format and appends its serde-bench
+/// encoding to `out`, for example to generate fixtures from JSON.
+///
+/// Unlike [`transcode_to`] this builds the whole `T` in between, since the
+/// source may present struct fields in any order but serde-bench encodes them
+/// in declaration order.
+pub fn transcode_from<'de, T, D>(out: &mut Vec<u8>, deserializer: D) -> Result<()>
+where
+    T: Deserialize<'de> + Serialize,
+    D: de::Deserializer<'de>,
+{
+    let value = T::deserialize(deserializer).map_err(Error::new)?;
+    crate::serialize(out, &value)
+}
+
+fn skipped() -> Error {
+    Error::new("`Deserialize` impl did not deserialize a value")
+}
+
+// Deserializes one value for the visitor of `T`, handing each decoded value
+// to `ser` on the way and leaving the serializer's output in `ok`.
+struct Transcoder<'a, 'de, S: Serializer> {
+    de: &'a mut Deserializer<SliceRead<'de>>,
+    ser: S,
+    ok: &'a mut Option<S::Ok>,
+}
+
+macro_rules! forward {
+    ($($method:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                self.de.$method(Forward {
+                    visitor,
+                    ser: self.ser,
+                    ok: self.ok,
+                })
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, S> de::Deserializer<'de> for Transcoder<'a, 'de, S>
+where
+    S: Serializer,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_any` is not supported"))
+    }
+
+    forward! {
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match u8::deserialize(&mut *self.de)? {
+            0 => {
+                *self.ok = Some(self.ser.serialize_none().map_err(Error::new)?);
+                visitor.visit_none()
+            }
+            1 => {
+                let element = Element::new(self.de, SomeSeed(visitor));
+                let outcome = self.ser.serialize_some(&element);
+                element.finish(outcome, self.ok)
+            }
+            _ => Err(Error::new("invalid Option")),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        *self.ok = Some(self.ser.serialize_unit().map_err(Error::new)?);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        *self.ok = Some(self.ser.serialize_unit_struct(name).map_err(Error::new)?);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == LEN {
+            let len = self.de.read_len()? as u64;
+            *self.ok = Some(Len(len).serialize(self.ser).map_err(Error::new)?);
+            return visitor.visit_u64(len);
+        }
+        if name == RAW {
+            return visitor.visit_newtype_struct(RawTranscoder {
+                de: self.de,
+                ser: self.ser,
+                ok: self.ok,
+            });
+        }
+        let element = Element::new(self.de, NewtypeSeed(visitor));
+        let outcome = self.ser.serialize_newtype_struct(name, &element);
+        element.finish(outcome, self.ok)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let len = self.de.read_len()?;
+        let state = self.ser.serialize_seq(Some(len)).map_err(Error::new)?;
+        visit_elements(self.de, Seq(state), len, visitor, self.ok)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let state = self.ser.serialize_tuple(len).map_err(Error::new)?;
+        visit_elements(self.de, Tuple(state), len, visitor, self.ok)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let state = self
+            .ser
+            .serialize_tuple_struct(name, len)
+            .map_err(Error::new)?;
+        visit_elements(self.de, TupleStruct(state), len, visitor, self.ok)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let len = self.de.read_len()?;
+        let state = self.ser.serialize_map(Some(len)).map_err(Error::new)?;
+        let mut access = Entries {
+            de: self.de,
+            state,
+            remaining: len,
+        };
+        let value = visitor.visit_map(&mut access)?;
+        *self.ok = Some(access.state.end().map_err(Error::new)?);
+        Ok(value)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let state = self
+            .ser
+            .serialize_struct(name, fields.len())
+            .map_err(Error::new)?;
+        let sink = Struct { state, fields };
+        visit_elements(self.de, sink, fields.len(), visitor, self.ok)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let index = self.de.read_variant_index()?;
+        visitor.visit_enum(Enum {
+            transcoder: self,
+            name,
+            variants,
+            index,
+        })
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_identifier` is not supported"))
+    }
+
+    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("`deserialize_ignored_any` is not supported"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+// The bytes of a `bulk` field, which have no length prefix: the visitor asks
+// for them as a tuple of the length it expects. They are passed on the way
+// `bulk` serializes them, as a `RAW` newtype of bytes.
+struct RawTranscoder<'a, 'de, S: Serializer> {
+    de: &'a mut Deserializer<SliceRead<'de>>,
+    ser: S,
+    ok: &'a mut Option<S::Ok>,
+}
+
+impl<'a, 'de, S> de::Deserializer<'de> for RawTranscoder<'a, 'de, S>
+where
+    S: Serializer,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::new("expected raw bytes"))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let bytes = self.de.read.read_slice(len)?;
+        *self.ok = Some(Raw(bytes).serialize(self.ser).map_err(Error::new)?);
+        visitor.visit_borrowed_bytes(bytes)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+// Serializes a value that is decoded only once the serializer asks for it,
+// keeping whatever the seed produced for the caller.
+struct Element<'a, 'de, X>
+where
+    X: DeserializeSeed<'de>,
+{
+    pending: Cell<Option<(&'a mut Deserializer<SliceRead<'de>>, X)>>,
+    result: Cell<Option<Result<X::Value>>>,
+}
+
+impl<'a, 'de, X> Element<'a, 'de, X>
+where
+    X: DeserializeSeed<'de>,
+{
+    fn new(de: &'a mut Deserializer<SliceRead<'de>>, seed: X) -> Self {
+        Element {
+            pending: Cell::new(Some((de, seed))),
+            result: Cell::new(None),
+        }
+    }
+
+    // Errors from decoding take precedence over the serializer's report of
+    // the same failure.
+    fn finish<T, E>(
+        self,
+        outcome: std::result::Result<T, E>,
+        ok: &mut Option<T>,
+    ) -> Result<X::Value>
+    where
+        E: fmt::Display,
+    {
+        match (self.result.into_inner(), outcome) {
+            (Some(Err(err)), _) => Err(err),
+            (_, Err(err)) => Err(Error::new(err)),
+            (Some(Ok(value)), Ok(output)) => {
+                *ok = Some(output);
+                Ok(value)
+            }
+            (None, Ok(_)) => Err(Error::new("serializer skipped a value")),
+        }
+    }
+}
+
+impl<'a, 'de, X> Serialize for Element<'a, 'de, X>
+where
+    X: DeserializeSeed<'de>,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let Some((de, seed)) = self.pending.take() else {
+            return Err(ser::Error::custom("value serialized more than once"));
+        };
+        let mut ok = None;
+        let result = seed.deserialize(Transcoder {
+            de,
+            ser: serializer,
+            ok: &mut ok,
+        });
+        let output = match &result {
+            Ok(_) => ok.ok_or_else(|| ser::Error::custom(skipped())),
+            Err(err) => Err(ser::Error::custom(err)),
+        };
+        self.result.set(Some(result));
+        output
+    }
+}
+
+struct SomeSeed<V>(V);
+
+impl<'de, V> DeserializeSeed<'de> for SomeSeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0.visit_some(deserializer)
+    }
+}
+
+struct NewtypeSeed<V>(V);
+
+impl<'de, V> DeserializeSeed<'de> for NewtypeSeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0.visit_newtype_struct(deserializer)
+    }
+}
+
+// The serializer state of a seq, tuple, struct or variant being transcoded.
+trait Sink {
+    type Ok;
+
+    fn element<X>(&mut self, index: usize, value: &X) -> Result<()>
+    where
+        X: ?Sized + Serialize;
+
+    fn end(self) -> Result<Self::Ok>;
+}
+
+macro_rules! sink {
+    ($($name:ident: $trait:ident::$method:ident,)*) => {
+        $(
+            struct $name<S>(S);
+
+            impl<S> Sink for $name<S>
+            where
+                S: ser::$trait,
+            {
+                type Ok = S::Ok;
+
+                fn element<X>(&mut self, _index: usize, value: &X) -> Result<()>
+                where
+                    X: ?Sized + Serialize,
+                {
+                    self.0.$method(value).map_err(Error::new)
+                }
+
+                fn end(self) -> Result<S::Ok> {
+                    self.0.end().map_err(Error::new)
+                }
+            }
+        )*
+    };
+}
+
+sink! {
+    Seq: SerializeSeq::serialize_element,
+    Tuple: SerializeTuple::serialize_element,
+    TupleStruct: SerializeTupleStruct::serialize_field,
+    TupleVariant: SerializeTupleVariant::serialize_field,
+}
+
+struct Struct<S> {
+    state: S,
+    fields: &'static [&'static str],
+}
+
+impl<S> Sink for Struct<S>
+where
+    S: ser::SerializeStruct,
+{
+    type Ok = S::Ok;
+
+    fn element<X>(&mut self, index: usize, value: &X) -> Result<()>
+    where
+        X: ?Sized + Serialize,
+    {
+        let key = self.fields.get(index).copied().unwrap_or("");
+        self.state.serialize_field(key, value).map_err(Error::new)
+    }
+
+    fn end(self) -> Result<S::Ok> {
+        self.state.end().map_err(Error::new)
+    }
+}
+
+struct StructVariant<S> {
+    state: S,
+    fields: &'static [&'static str],
+}
+
+impl<S> Sink for StructVariant<S>
+where
+    S: ser::SerializeStructVariant,
+{
+    type Ok = S::Ok;
+
+    fn element<X>(&mut self, index: usize, value: &X) -> Result<()>
+    where
+        X: ?Sized + Serialize,
+    {
+        let key = self.fields.get(index).copied().unwrap_or("");
+        self.state.serialize_field(key, value).map_err(Error::new)
+    }
+
+    fn end(self) -> Result<S::Ok> {
+        self.state.end().map_err(Error::new)
+    }
+}
+
+fn visit_elements<'de, K, V>(
+    de: &mut Deserializer<SliceRead<'de>>,
+    sink: K,
+    len: usize,
+    visitor: V,
+    ok: &mut Option<K::Ok>,
+) -> Result<V::Value>
+where
+    K: Sink,
+    V: Visitor<'de>,
+{
+    let mut access = Elements {
+        de,
+        sink,
+        remaining: len,
+        index: 0,
+    };
+    let value = visitor.visit_seq(&mut access)?;
+    *ok = Some(access.sink.end()?);
+    Ok(value)
+}
+
+struct Elements<'a, 'de, K> {
+    de: &'a mut Deserializer<SliceRead<'de>>,
+    sink: K,
+    remaining: usize,
+    index: usize,
+}
+
+impl<'a, 'de, K> SeqAccess<'de> for Elements<'a, 'de, K>
+where
+    K: Sink,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let element = Element::new(self.de, seed);
+        let outcome = self.sink.element(self.index, &element);
+        self.index += 1;
+        element.finish(outcome, &mut None).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct Entries<'a, 'de, S> {
+    de: &'a mut Deserializer<SliceRead<'de>>,
+    state: S,
+    remaining: usize,
+}
+
+impl<'a, 'de, S> MapAccess<'de> for Entries<'a, 'de, S>
+where
+    S: SerializeMap,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let element = Element::new(self.de, seed);
+        let outcome = self.state.serialize_key(&element);
+        element.finish(outcome, &mut None).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let element = Element::new(self.de, seed);
+        let outcome = self.state.serialize_value(&element);
+        element.finish(outcome, &mut None)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+struct Enum<'a, 'de, S: Serializer> {
+    transcoder: Transcoder<'a, 'de, S>,
+    name: &'static str,
+    variants: &'static [&'static str],
+    index: u32,
+}
+
+impl<'a, 'de, S> Enum<'a, 'de, S>
+where
+    S: Serializer,
+{
+    fn variant_name(&self) -> Result<&'static str> {
+        match self.variants.get(self.index as usize) {
+            Some(variant) => Ok(variant),
+            None => Err(Error::new(format_args!(
+                "variant index {} out of range for {}",
+                self.index, self.name,
+            ))),
+        }
+    }
+}
+
+impl<'a, 'de, S> EnumAccess<'de> for Enum<'a, 'de, S>
+where
+    S: Serializer,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let deserializer = <u32 as IntoDeserializer<Error>>::into_deserializer(self.index);
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'de, S> VariantAccess<'de> for Enum<'a, 'de, S>
+where
+    S: Serializer,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        let variant = self.variant_name()?;
+        let Transcoder { ser, ok, .. } = self.transcoder;
+        let output = ser
+            .serialize_unit_variant(self.name, self.index, variant)
+            .map_err(Error::new)?;
+        *ok = Some(output);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let variant = self.variant_name()?;
+        let Transcoder { de, ser, ok } = self.transcoder;
+        let element = Element::new(de, seed);
+        let outcome = ser.serialize_newtype_variant(self.name, self.index, variant, &element);
+        element.finish(outcome, ok)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let variant = self.variant_name()?;
+        let Transcoder { de, ser, ok } = self.transcoder;
+        let state = ser
+            .serialize_tuple_variant(self.name, self.index, variant, len)
+            .map_err(Error::new)?;
+        visit_elements(de, TupleVariant(state), len, visitor, ok)
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let variant = self.variant_name()?;
+        let Transcoder { de, ser, ok } = self.transcoder;
+        let state = ser
+            .serialize_struct_variant(self.name, self.index, variant, fields.len())
+            .map_err(Error::new)?;
+        let sink = StructVariant { state, fields };
+        visit_elements(de, sink, fields.len(), visitor, ok)
+    }
+}
+
+// Passes each decoded primitive to the serializer before the visitor.
+struct Forward<'a, V, S: Serializer> {
+    visitor: V,
+    ser: S,
+    ok: &'a mut Option<S::Ok>,
+}
+
+macro_rules! forward_visit {
+    ($($visit:ident($ty:ty) => $serialize:ident,)*) => {
+        $(
+            fn $visit<E>(self, v: $ty) -> std::result::Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                *self.ok = Some(self.ser.$serialize(v).map_err(E::custom)?);
+                self.visitor.$visit(v)
+            }
+        )*
+    };
+}
+
+impl<'a, 'de, V, S> Visitor<'de> for Forward<'a, V, S>
+where
+    V: Visitor<'de>,
+    S: Serializer,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool) => serialize_bool,
+        visit_i8(i8) => serialize_i8,
+        visit_i16(i16) => serialize_i16,
+        visit_i32(i32) => serialize_i32,
+        visit_i64(i64) => serialize_i64,
+        visit_i128(i128) => serialize_i128,
+        visit_u8(u8) => serialize_u8,
+        visit_u16(u16) => serialize_u16,
+        visit_u32(u32) => serialize_u32,
+        visit_u64(u64) => serialize_u64,
+        visit_u128(u128) => serialize_u128,
+        visit_f32(f32) => serialize_f32,
+        visit_f64(f64) => serialize_f64,
+        visit_char(char) => serialize_char,
+        visit_str(&str) => serialize_str,
+        visit_borrowed_str(&'de str) => serialize_str,
+        visit_bytes(&[u8]) => serialize_bytes,
+        visit_borrowed_bytes(&'de [u8]) => serialize_bytes,
+    }
+}
@@ -0,0 +1,42 @@
+// LEB128 varints, 7 bits per byte with the least significant group first, as
+// used by `Width::Varint` and `LengthPrefix::Varint`.
+
+use crate::{Error, Result};
+use byteorder::ReadBytesExt;
+use std::io::{self, Read, Write};
+
+pub(crate) fn write<W: Write>(writer: &mut W, mut n: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut i = 0;
+    while n >= 0x80 {
+        buf[i] = n as u8 | 0x80;
+        n >>= 7;
+        i += 1;
+    }
+    buf[i] = n as u8;
+    writer.write_all(&buf[..=i])
+}
+
+// Kept out of line so that callers choosing between fixed widths and varints
+// stay small enough to inline.
+#[inline(never)]
+pub(crate) fn read<R: Read>(reader: &mut R) -> Result<u64> {
+    let first = reader.read_u8()?;
+    read_after(reader, first)
+}
+
+// Finishes a varint whose first byte the caller has already read.
+pub(crate) fn read_after<R: Read>(reader: &mut R, first: u8) -> Result<u64> {
+    let mut n = u64::from(first & 0x7F);
+    let mut byte = first;
+    let mut shift = 7;
+    while byte & 0x80 != 0 {
+        byte = reader.read_u8()?;
+        if shift == 63 && byte > 1 {
+            return Err(Error::new("varint overflows u64"));
+        }
+        n |= u64::from(byte & 0x7F) << shift;
+        shift += 7;
+    }
+    Ok(n)
+}
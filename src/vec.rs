@@ -0,0 +1,333 @@
+use crate::bulk::{LenSerializer, RawSerializer, LEN, RAW};
+use crate::config::{Config, Width};
+use crate::{Error, Result};
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+
+/// Serializer that appends straight to a `Vec<u8>`.
+///
+/// Produces the same bytes as [`serialize`](crate::serialize), but pushes into
+/// the vector instead of going through `io::Write`, so none of the primitives
+/// can fail. The only errors are those returned by `Serialize` impls
+/// themselves, and lengths or variant indices too large for a non-default
+/// [`Config`].
+pub struct VecSerializer<'a> {
+    out: &'a mut Vec<u8>,
+    config: Config,
+}
+
+impl<'a> VecSerializer<'a> {
+    #[must_use]
+    pub fn new(out: &'a mut Vec<u8>) -> Self {
+        VecSerializer {
+            out,
+            config: Config::new(),
+        }
+    }
+
+    /// Reserves `size_hint` bytes up front, so that a value no larger than the
+    /// hint is written without reallocating.
+    #[must_use]
+    pub fn with_size_hint(out: &'a mut Vec<u8>, size_hint: usize) -> Self {
+        out.reserve(size_hint);
+        VecSerializer::new(out)
+    }
+
+    /// Writes variant indices and lengths with the widths from `config`
+    /// instead of the defaults.
+    #[must_use]
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Only a non-default `Config` can fail here.
+    #[inline]
+    fn push_len(&mut self, len: usize) -> Result<()> {
+        if self.config.length == Width::U64 {
+            self.out.extend_from_slice(&(len as u64).to_ne_bytes());
+            Ok(())
+        } else {
+            write_len(self.config, self.out, len as u64)
+        }
+    }
+
+    #[inline]
+    fn push_variant_index(&mut self, variant_index: u32) -> Result<()> {
+        if self.config.variant_index == Width::U32 {
+            self.out.extend_from_slice(&variant_index.to_ne_bytes());
+            Ok(())
+        } else {
+            write_variant_index(self.config, self.out, variant_index)
+        }
+    }
+}
+
+// Non-default widths, kept out of line so that the default path stays small.
+#[inline(never)]
+fn write_len(config: Config, out: &mut Vec<u8>, len: u64) -> Result<()> {
+    config.write_len(out, len)
+}
+
+#[inline(never)]
+fn write_variant_index(config: Config, out: &mut Vec<u8>, variant_index: u32) -> Result<()> {
+    config.write_variant_index(out, variant_index)
+}
+
+macro_rules! impl_nums {
+    ($($method:ident($ty:ty),)*) => {
+        $(
+            #[inline]
+            fn $method(self, v: $ty) -> Result<()> {
+                self.out.extend_from_slice(&v.to_ne_bytes());
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl<'a> serde::Serializer for &mut VecSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    #[inline]
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.out.push(v as u8);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.out.push(v);
+        Ok(())
+    }
+
+    impl_nums! {
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    #[inline]
+    fn serialize_char(self, c: char) -> Result<()> {
+        self.out
+            .extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.out.reserve(8 + v.len());
+        self.push_len(v.len())?;
+        self.out.extend_from_slice(v);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<()> {
+        self.out.push(0);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, v: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.out.push(1);
+        v.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        let len = len.expect("do not know how to serialize a sequence with no length");
+        self.push_len(len)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.push_variant_index(variant_index)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        let len = len.expect("do not know how to serialize a map with no length");
+        self.push_len(len)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.push_variant_index(variant_index)?;
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if name == RAW {
+            return value.serialize(RawSerializer { writer: self.out });
+        }
+        if name == LEN {
+            let len = value.serialize(LenSerializer)?;
+            return self.config.write_len(self.out, len);
+        }
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push_variant_index(variant_index)?;
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        self.push_variant_index(variant_index)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! impl_compound {
+    ($($trait:ident::$method:ident($($key:ty)?),)*) => {
+        $(
+            impl<'a> $trait for &mut VecSerializer<'a> {
+                type Ok = ();
+                type Error = Error;
+
+                #[inline]
+                fn $method<V>(&mut self, $(_key: $key,)? value: &V) -> Result<()>
+                where
+                    V: ?Sized + Serialize,
+                {
+                    value.serialize(&mut **self)
+                }
+
+                #[inline]
+                fn end(self) -> Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_compound! {
+    SerializeSeq::serialize_element(),
+    SerializeTuple::serialize_element(),
+    SerializeTupleStruct::serialize_field(),
+    SerializeTupleVariant::serialize_field(),
+    SerializeStruct::serialize_field(&'static str),
+    SerializeStructVariant::serialize_field(&'static str),
+}
+
+impl<'a> SerializeMap for &mut VecSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    #[inline]
+    fn serialize_key<K>(&mut self, key: &K) -> Result<()>
+    where
+        K: ?Sized + Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn serialize_value<V>(&mut self, value: &V) -> Result<()>
+    where
+        V: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "tokio")]
+
+use serde::{Deserialize, Serialize};
+use serde_bench::{FrameReader, FrameWriter};
+use tokio::io::AsyncWriteExt;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message {
+    id: u64,
+    name: String,
+    payload: Vec<u32>,
+}
+
+fn messages() -> Vec<Message> {
+    (0..10u32)
+        .map(|id| Message {
+            id: u64::from(id),
+            name: format!("message {id}"),
+            payload: vec![id; 100],
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_loopback() {
+    // Smaller than one frame, so the writer has to wait for the reader.
+    let (mut client, mut server) = tokio::io::duplex(64);
+    let expected = messages();
+
+    let write = async {
+        for message in &expected {
+            serde_bench::serialize_async(&mut client, message)
+                .await
+                .unwrap();
+        }
+        client.shutdown().await.unwrap();
+    };
+    let read = async {
+        let mut received = Vec::new();
+        while let Some(message) = serde_bench::deserialize_async::<_, Message>(&mut server)
+            .await
+            .unwrap()
+        {
+            received.push(message);
+        }
+        received
+    };
+
+    let ((), received) = tokio::join!(write, read);
+    assert_eq!(received, expected);
+}
+
+#[tokio::test]
+async fn test_frame_interop() {
+    let mut out = Vec::new();
+    for message in &messages() {
+        serde_bench::serialize_async(&mut out, message)
+            .await
+            .unwrap();
+    }
+    let mut reader = FrameReader::new(&out[..]);
+    for message in messages() {
+        assert_eq!(reader.read::<Message>().unwrap(), Some(message));
+    }
+    assert_eq!(reader.read::<Message>().unwrap(), None);
+
+    let mut writer = FrameWriter::new(Vec::new());
+    for message in &messages() {
+        writer.write(message).unwrap();
+    }
+    let bytes = writer.into_inner();
+    let mut input = &bytes[..];
+    for message in messages() {
+        let received = serde_bench::deserialize_async(&mut input).await.unwrap();
+        assert_eq!(received, Some(message));
+    }
+    let end = serde_bench::deserialize_async::<_, Message>(&mut input).await;
+    assert!(end.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_truncated() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_async(&mut bytes, &"hello")
+        .await
+        .unwrap();
+
+    for len in 1..bytes.len() {
+        let mut input = &bytes[..len];
+        let err = serde_bench::deserialize_async::<_, String>(&mut input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "unexpected end of input");
+    }
+}
+
+#[tokio::test]
+async fn test_trailing_bytes() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_async(&mut bytes, &(1u32, 2u32))
+        .await
+        .unwrap();
+
+    let mut input = &bytes[..];
+    let err = serde_bench::deserialize_async::<_, u32>(&mut input)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "trailing bytes in frame");
+}
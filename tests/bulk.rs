@@ -0,0 +1,119 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_bench::StreamDeserializer;
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Bulk {
+    #[serde(with = "serde_bench::bulk")]
+    floats: Vec<f64>,
+    #[serde(with = "serde_bench::bulk")]
+    shorts: Vec<i16>,
+    #[serde(with = "serde_bench::bulk")]
+    bytes: Vec<u8>,
+    #[serde(with = "serde_bench::bulk::array")]
+    array: [u32; 5],
+    #[serde(with = "serde_bench::bulk")]
+    empty: Vec<u128>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Plain {
+    floats: Vec<f64>,
+    shorts: Vec<i16>,
+    bytes: Vec<u8>,
+    array: [u32; 5],
+    empty: Vec<u128>,
+}
+
+fn bulk() -> Bulk {
+    Bulk {
+        floats: vec![0.5, -1.25, f64::MAX],
+        shorts: vec![1, -2, i16::MIN],
+        bytes: b"bytes".to_vec(),
+        array: [1, 2, 3, 4, u32::MAX],
+        empty: Vec::new(),
+    }
+}
+
+fn plain() -> Plain {
+    let Bulk {
+        floats,
+        shorts,
+        bytes,
+        array,
+        empty,
+    } = bulk();
+    Plain {
+        floats,
+        shorts,
+        bytes,
+        array,
+        empty,
+    }
+}
+
+fn serialize<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, value).unwrap();
+    bytes
+}
+
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> T {
+    serde_bench::deserialize(bytes).unwrap()
+}
+
+#[test]
+fn test_same_bytes_as_elementwise() {
+    assert_eq!(serialize(&bulk()), serialize(&plain()));
+}
+
+#[test]
+fn test_roundtrip() {
+    let bytes = serialize(&bulk());
+    assert_eq!(deserialize::<Bulk>(&bytes), bulk());
+    assert_eq!(deserialize::<Plain>(&bytes), plain());
+    assert_eq!(deserialize::<Bulk>(&serialize(&plain())), bulk());
+}
+
+#[test]
+fn test_reader() {
+    let bytes = serialize(&bulk());
+    let mut stream = StreamDeserializer::<_, Bulk>::from_reader(bytes.as_slice());
+    assert_eq!(stream.next().unwrap().unwrap(), bulk());
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn test_truncated() {
+    let mut bytes = serialize(&bulk());
+    bytes.truncate(20);
+    let err = serde_bench::deserialize::<Bulk>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of input");
+}
+
+#[test]
+fn test_length_overflow() {
+    #[derive(Deserialize, Debug)]
+    struct Floats {
+        #[serde(with = "serde_bench::bulk")]
+        _floats: Vec<f64>,
+    }
+
+    let bytes = serialize(&u64::MAX);
+    let err = serde_bench::deserialize::<Floats>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), "length overflows usize");
+}
+
+fn json_roundtrip<T>(value: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), *value);
+}
+
+#[test]
+fn test_other_format() {
+    json_roundtrip(&bulk());
+}
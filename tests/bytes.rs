@@ -0,0 +1,117 @@
+#![cfg(feature = "bytes")]
+
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use serde_bench::ErrorKind;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Packet {
+    id: u32,
+    #[serde(with = "serde_bench::bytes")]
+    header: Bytes,
+    #[serde(with = "serde_bench::bytes")]
+    payload: Bytes,
+}
+
+fn packet() -> Packet {
+    Packet {
+        id: 1337,
+        header: Bytes::from_static(b"header"),
+        payload: Bytes::from(vec![0xAB; 1000]),
+    }
+}
+
+fn contains(outer: &Bytes, inner: &Bytes) -> bool {
+    let range = outer.as_ptr_range();
+    range.start <= inner.as_ptr() && inner.as_ptr_range().end <= range.end
+}
+
+#[test]
+fn test_same_bytes() {
+    let value = (packet(), vec!["a", "b"], Some(7u64));
+    let mut vec = Vec::new();
+    serde_bench::serialize(&mut vec, &value).unwrap();
+
+    let mut buf = BytesMut::new();
+    buf.put_u8(0xFF);
+    serde_bench::serialize_into_buf(&mut buf, &value).unwrap();
+    assert_eq!(buf[0], 0xFF);
+    assert_eq!(&buf[1..], &vec[..]);
+}
+
+#[test]
+fn test_same_as_serde_bytes() {
+    #[derive(Serialize)]
+    struct Vecs {
+        id: u32,
+        #[serde(with = "serde_bytes")]
+        header: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        payload: Vec<u8>,
+    }
+
+    let packet = packet();
+    let vecs = Vecs {
+        id: packet.id,
+        header: packet.header.to_vec(),
+        payload: packet.payload.to_vec(),
+    };
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, &vecs).unwrap();
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &packet).unwrap();
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn test_zero_copy() {
+    let mut buf = BytesMut::new();
+    serde_bench::serialize_into_buf(&mut buf, &packet()).unwrap();
+    let bytes = buf.freeze();
+
+    let decoded: Packet = serde_bench::deserialize_from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, packet());
+    assert!(contains(&bytes, &decoded.header));
+    assert!(contains(&bytes, &decoded.payload));
+
+    // Still valid once the input is gone.
+    drop(bytes);
+    assert_eq!(decoded, packet());
+}
+
+#[test]
+fn test_copy_without_source() {
+    let mut vec = Vec::new();
+    serde_bench::serialize(&mut vec, &packet()).unwrap();
+    let decoded: Packet = serde_bench::deserialize(&vec).unwrap();
+    assert_eq!(decoded, packet());
+
+    let json = serde_json::to_string(&packet()).unwrap();
+    let decoded: Packet = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, packet());
+}
+
+#[test]
+fn test_nested() {
+    let mut inner = Vec::new();
+    serde_bench::serialize(&mut inner, &packet()).unwrap();
+    let inner = Bytes::from(inner);
+    let mut outer = Vec::new();
+    serde_bench::serialize(&mut outer, &packet()).unwrap();
+    let outer = Bytes::from(outer);
+
+    let first: Packet = serde_bench::deserialize_from_bytes(&outer).unwrap();
+    let nested: Packet = serde_bench::deserialize_from_bytes(&inner).unwrap();
+    let second: Packet = serde_bench::deserialize_from_bytes(&outer).unwrap();
+    assert!(contains(&outer, &first.payload));
+    assert!(contains(&inner, &nested.payload));
+    assert!(contains(&outer, &second.payload));
+}
+
+#[test]
+fn test_full_buf() {
+    let mut array = [0; 16];
+    let mut buf = &mut array[..];
+    let err = serde_bench::serialize_into_buf(&mut buf, &packet()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+}
@@ -0,0 +1,68 @@
+#![cfg(any(feature = "crc32fast", feature = "xxhash-rust"))]
+
+use serde_bench::{Checksum, ErrorKind};
+
+fn checksums() -> Vec<Checksum> {
+    vec![
+        #[cfg(feature = "crc32fast")]
+        Checksum::Crc32,
+        #[cfg(feature = "xxhash-rust")]
+        Checksum::XxHash64,
+    ]
+}
+
+#[test]
+fn test_roundtrip() {
+    let value = (1337u64, "checksummed".to_owned(), vec![true, false]);
+    for checksum in checksums() {
+        let mut bytes = Vec::new();
+        serde_bench::serialize_with_checksum(&mut bytes, &value, checksum).unwrap();
+
+        let mut plain = Vec::new();
+        serde_bench::serialize(&mut plain, &value).unwrap();
+        assert!(bytes.starts_with(&plain));
+
+        let decoded: (u64, String, Vec<bool>) =
+            serde_bench::deserialize_with_checksum(&bytes, checksum).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
+
+#[cfg(feature = "crc32fast")]
+#[test]
+fn test_crc32_trailer() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_checksum(&mut bytes, b"123456789", Checksum::Crc32).unwrap();
+    // The array is encoded as its bytes, so this is the CRC-32 check value.
+    assert_eq!(bytes[bytes.len() - 4..], 0xCBF4_3926u32.to_ne_bytes());
+}
+
+#[cfg(feature = "xxhash-rust")]
+#[test]
+fn test_xxhash64_trailer() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_checksum(&mut bytes, &(), Checksum::XxHash64).unwrap();
+    // XXH64 of the empty input with seed 0.
+    assert_eq!(bytes, 0xEF46_DB37_51D8_E999u64.to_ne_bytes());
+}
+
+#[test]
+fn test_corruption() {
+    let value = vec![1u32, 2, 3];
+    for checksum in checksums() {
+        let mut bytes = Vec::new();
+        serde_bench::serialize_with_checksum(&mut bytes, &value, checksum).unwrap();
+
+        for i in 0..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[i] ^= 0x10;
+            let err = serde_bench::deserialize_with_checksum::<Vec<u32>>(&corrupted, checksum)
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ChecksumMismatch);
+        }
+
+        let err =
+            serde_bench::deserialize_with_checksum::<Vec<u32>>(&bytes[..2], checksum).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+    }
+}
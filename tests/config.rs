@@ -0,0 +1,142 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_bench::{Config, VecSerializer, Width};
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Enum {
+    A,
+    B(String),
+    C { v: Vec<u16> },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Bulk(#[serde(with = "serde_bench::bulk")] Vec<u32>);
+
+const WIDTHS: [Width; 5] = [Width::U8, Width::U16, Width::U32, Width::U64, Width::Varint];
+
+fn roundtrip<T>(config: Config, value: &T) -> Vec<u8>
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_config(&mut bytes, value, config).unwrap();
+    let deserialized: T = serde_bench::deserialize_with_config(&bytes, config).unwrap();
+    assert_eq!(deserialized, *value, "{config:?}");
+
+    let mut vec = Vec::new();
+    let mut ser = VecSerializer::new(&mut vec).config(config);
+    value.serialize(&mut ser).unwrap();
+    assert_eq!(vec, bytes, "{config:?}");
+    bytes
+}
+
+#[test]
+fn test_roundtrip() {
+    let value = vec![
+        Enum::A,
+        Enum::B("x".repeat(200)),
+        Enum::C { v: vec![7; 130] },
+    ];
+    for variant_index in WIDTHS {
+        for length in WIDTHS {
+            let config = Config::new().variant_index(variant_index).length(length);
+            roundtrip(config, &value);
+            roundtrip(config, &Bulk((0..200).collect()));
+        }
+    }
+}
+
+#[test]
+fn test_default() {
+    let value = Enum::C { v: vec![1, 2] };
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, &value).unwrap();
+    assert_eq!(roundtrip(Config::default(), &value), expected);
+}
+
+#[test]
+fn test_varint_length() {
+    let config = Config::new().length(Width::Varint);
+    let bytes = roundtrip(config, &"y".repeat(300));
+    assert_eq!(bytes[..2], [0xac, 0x02]);
+    assert_eq!(bytes.len(), 302);
+}
+
+#[test]
+fn test_bulk_length() {
+    let config = Config::new().length(Width::U16);
+    let bytes = roundtrip(config, &Bulk(vec![1, 2, 3]));
+    assert_eq!(bytes.len(), 2 + 12);
+}
+
+#[test]
+fn test_length_out_of_range() {
+    let config = Config::new().length(Width::U8);
+    let mut bytes = Vec::new();
+    let err = serde_bench::serialize_with_config(&mut bytes, &vec![0u8; 256], config).unwrap_err();
+    assert_eq!(err.to_string(), "length 256 out of range for u8");
+
+    let mut vec = Vec::new();
+    let mut ser = VecSerializer::new(&mut vec).config(config);
+    let err = "z".repeat(1000).serialize(&mut ser).unwrap_err();
+    assert_eq!(err.to_string(), "length 1000 out of range for u8");
+
+    let config = Config::new().length(Width::U16);
+    let err =
+        serde_bench::serialize_with_config(&mut bytes, &Bulk(vec![0; 65536]), config).unwrap_err();
+    assert_eq!(err.to_string(), "length 65536 out of range for u16");
+}
+
+#[test]
+fn test_variant_index_out_of_range() {
+    struct Index(u32);
+
+    impl Serialize for Index {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_unit_variant("Index", self.0, "V")
+        }
+    }
+
+    let config = Config::new().variant_index(Width::U8);
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_config(&mut bytes, &Index(255), config).unwrap();
+    assert_eq!(bytes, [255]);
+
+    let err = serde_bench::serialize_with_config(&mut bytes, &Index(300), config).unwrap_err();
+    assert_eq!(err.to_string(), "variant index 300 out of range for u8");
+
+    let config = Config::new().variant_index(Width::U16);
+    let err = serde_bench::serialize_with_config(&mut bytes, &Index(70000), config).unwrap_err();
+    assert_eq!(err.to_string(), "variant index 70000 out of range for u16");
+}
+
+#[test]
+fn test_variant_index_too_large_to_read() {
+    let config = Config::new().variant_index(Width::U64);
+    let bytes = (u64::from(u32::MAX) + 1).to_ne_bytes();
+    let err = serde_bench::deserialize_with_config::<Enum>(&bytes, config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value: integer `4294967296`, expected u32",
+    );
+}
+
+#[test]
+fn test_varint_overflow() {
+    let config = Config::new().length(Width::Varint);
+    let mut bytes = vec![0xff; 9];
+    bytes.push(0x02);
+    let err = serde_bench::deserialize_with_config::<String>(&bytes, config).unwrap_err();
+    assert_eq!(err.to_string(), "varint overflows u64");
+}
+
+#[test]
+fn test_truncated() {
+    let config = Config::new().length(Width::U32);
+    let err = serde_bench::deserialize_with_config::<String>(&[1, 0], config).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of input");
+}
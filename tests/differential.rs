@@ -0,0 +1,138 @@
+// Byte-for-byte equality with bincode only holds where both are little-endian.
+#![cfg(target_endian = "little")]
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+use proptest::{num, option};
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
+
+// Covers every type in the Serde data model, nested arbitrarily.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+enum Value {
+    Unit,
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Bytes(ByteBuf),
+    Option(Option<Box<Value>>),
+    UnitStruct(UnitStruct),
+    NewtypeStruct(NewtypeStruct),
+    TupleStruct(TupleStruct),
+    Struct(Struct),
+    Tuple((u8, Box<Value>)),
+    Array([u16; 3]),
+    Seq(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+    TupleVariant(char, Box<Value>),
+    StructVariant { a: u32, b: Box<Value> },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct UnitStruct;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct NewtypeStruct(Box<Value>);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct TupleStruct(i32, Box<Value>, bool);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Struct {
+    a: Option<char>,
+    b: Box<Value>,
+    c: String,
+}
+
+fn value() -> impl Strategy<Value = Value> {
+    // NaN is excluded only because it is not equal to itself.
+    let f32 = num::f32::ANY.prop_filter("NaN", |f| !f.is_nan());
+    let f64 = num::f64::ANY.prop_filter("NaN", |f| !f.is_nan());
+
+    let leaf = prop_oneof![
+        Just(Value::Unit),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i8>().prop_map(Value::I8),
+        any::<i16>().prop_map(Value::I16),
+        any::<i32>().prop_map(Value::I32),
+        any::<i64>().prop_map(Value::I64),
+        any::<i128>().prop_map(Value::I128),
+        any::<u8>().prop_map(Value::U8),
+        any::<u16>().prop_map(Value::U16),
+        any::<u32>().prop_map(Value::U32),
+        any::<u64>().prop_map(Value::U64),
+        any::<u128>().prop_map(Value::U128),
+        f32.prop_map(Value::F32),
+        f64.prop_map(Value::F64),
+        any::<char>().prop_map(Value::Char),
+        any::<String>().prop_map(Value::String),
+        vec(any::<u8>(), 0..32).prop_map(|bytes| Value::Bytes(ByteBuf::from(bytes))),
+        Just(Value::UnitStruct(UnitStruct)),
+        any::<[u16; 3]>().prop_map(Value::Array),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        let boxed = inner.clone().prop_map(Box::new);
+        prop_oneof![
+            option::of(boxed.clone()).prop_map(Value::Option),
+            boxed
+                .clone()
+                .prop_map(|value| Value::NewtypeStruct(NewtypeStruct(value))),
+            (any::<i32>(), boxed.clone(), any::<bool>())
+                .prop_map(|(a, b, c)| Value::TupleStruct(TupleStruct(a, b, c))),
+            (option::of(any::<char>()), boxed.clone(), any::<String>())
+                .prop_map(|(a, b, c)| Value::Struct(Struct { a, b, c })),
+            (any::<u8>(), boxed.clone()).prop_map(Value::Tuple),
+            vec(inner.clone(), 0..8).prop_map(Value::Seq),
+            btree_map(any::<String>(), inner, 0..8).prop_map(Value::Map),
+            (any::<char>(), boxed.clone()).prop_map(|(a, b)| Value::TupleVariant(a, b)),
+            (any::<u32>(), boxed).prop_map(|(a, b)| Value::StructVariant { a, b }),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_same_bytes_as_bincode(value in value()) {
+        let bincode_bytes = bincode::serde::encode_to_vec(&value, bincode::config::legacy()).unwrap();
+
+        let mut serde_bytes = Vec::new();
+        serde_bench::serialize(&mut serde_bytes, &value).unwrap();
+
+        prop_assert_eq!(bincode_bytes, serde_bytes);
+    }
+
+    #[test]
+    fn test_decode_bincode(value in value()) {
+        let bytes = bincode::serde::encode_to_vec(&value, bincode::config::legacy()).unwrap();
+
+        let serde_value = serde_bench::deserialize::<Value>(&bytes).unwrap();
+        prop_assert_eq!(serde_value, value);
+    }
+
+    #[test]
+    fn test_bincode_decodes(value in value()) {
+        let mut bytes = Vec::new();
+        serde_bench::serialize(&mut bytes, &value).unwrap();
+
+        let (bincode_value, len) =
+            bincode::serde::decode_from_slice::<Value, _>(&bytes, bincode::config::legacy())
+                .unwrap();
+        prop_assert_eq!(len, bytes.len());
+        prop_assert_eq!(bincode_value, value);
+    }
+}
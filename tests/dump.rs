@@ -0,0 +1,130 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+struct Foo<'a> {
+    id: u32,
+    name: &'a str,
+    tags: Vec<u8>,
+    kind: Kind,
+}
+
+#[derive(Deserialize)]
+enum Kind {
+    Unit,
+    Newtype(i16),
+    Struct { x: bool },
+}
+
+#[derive(Deserialize)]
+struct Samples {
+    id: u8,
+    #[serde(with = "serde_bench::bulk::array")]
+    points: [u16; 3],
+    #[serde(with = "serde_bench::bulk")]
+    values: Vec<i32>,
+}
+
+fn serialize<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, value).unwrap();
+    bytes
+}
+
+#[cfg(target_endian = "little")]
+#[test]
+fn test_struct() {
+    let bytes = serialize(&(5u32, "héllo", vec![1u8, 2], 2u32, true));
+    let expected = "\
+00000000                              Foo
+00000000  05 00 00 00                   id: u32 = 5
+00000004  06 00 00 00 00 00 00 00 ..    name: str, len 6 = \"héllo\"
+00000012  02 00 00 00 00 00 00 00       tags: seq, len 2
+0000001a  01                              [0] u8 = 1
+0000001b  02                              [1] u8 = 2
+0000001c  02 00 00 00                   kind: Kind::Struct, index 2
+00000020  01                              x: bool = true
+";
+    assert_eq!(serde_bench::dump::<Foo>(&bytes), expected);
+}
+
+#[cfg(target_endian = "little")]
+#[test]
+fn test_option_and_newtype_variant() {
+    // Some(vec![Kind::Newtype(-2)])
+    let bytes = serialize(&(1u8, 1u64, 1u32, -2i16));
+    let expected = "\
+00000000  01                          Some
+00000001  01 00 00 00 00 00 00 00       seq, len 1
+00000009  01 00 00 00                     [0] Kind::Newtype, index 1
+0000000d  fe ff                             i16 = -2
+";
+    assert_eq!(serde_bench::dump::<Option<Vec<Kind>>>(&bytes), expected);
+}
+
+#[cfg(target_endian = "little")]
+#[test]
+fn test_map() {
+    let mut map = BTreeMap::new();
+    map.insert(1u8, 'x');
+    let expected = "\
+00000000  01 00 00 00 00 00 00 00     map, len 1
+00000008  01                            [0] key: u8 = 1
+00000009  78                            [0] value: char = 'x'
+";
+    assert_eq!(
+        serde_bench::dump::<BTreeMap<u8, char>>(&serialize(&map)),
+        expected,
+    );
+}
+
+#[cfg(target_endian = "little")]
+#[test]
+fn test_bulk() {
+    let bytes = serialize(&(1u8, [1u16, 2, 3], vec![-1i32, 4]));
+    let expected = "\
+00000000                              Samples
+00000000  01                            id: u8 = 1
+00000001  01 00 02 00 03 00             points: raw, len 6
+00000007                                values: tuple, len 2
+00000007  02 00 00 00 00 00 00 00         [0] len = 2
+0000000f  ff ff ff ff 04 00 00 00         [1] raw, len 8
+";
+    assert_eq!(serde_bench::dump::<Samples>(&bytes), expected);
+}
+
+#[test]
+fn test_trailing_bytes() {
+    let dump = serde_bench::dump::<u8>(&[7, 8, 9]);
+    assert_eq!(
+        dump.lines().last(),
+        Some("00000001  08 09                       2 trailing bytes"),
+    );
+}
+
+#[test]
+fn test_error_offset() {
+    let mut bytes = serialize(&(1u8, "ab"));
+    bytes.truncate(bytes.len() - 1);
+    let dump = serde_bench::dump::<(u8, String)>(&bytes);
+    let last = dump.lines().last().unwrap();
+    assert!(last.starts_with("00000001  "), "{dump}");
+    assert!(last.ends_with("error: unexpected end of input"), "{dump}");
+}
+
+#[test]
+fn test_invalid_variant() {
+    let bytes = serialize(&7u32);
+    let dump = serde_bench::dump::<Kind>(&bytes);
+    let mut lines = dump.lines();
+    assert!(
+        lines.next().unwrap().ends_with("Kind::?, index 7"),
+        "{dump}"
+    );
+    assert!(
+        lines.next().unwrap().contains("error: invalid value"),
+        "{dump}"
+    );
+}
@@ -0,0 +1,168 @@
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "crc32fast")]
+use serde_bench::{Checksum, ErrorKind};
+use serde_bench::{FrameConfig, FrameReader, FrameWriter, LengthPrefix, StreamDeserializer};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message {
+    id: u64,
+    body: String,
+}
+
+fn messages() -> Vec<Message> {
+    [0, 1, 300, 70000]
+        .into_iter()
+        .map(|len| Message {
+            id: len as u64,
+            body: "x".repeat(len),
+        })
+        .collect()
+}
+
+fn roundtrip(config: FrameConfig) -> Vec<u8> {
+    let mut writer = FrameWriter::with_config(Vec::new(), config);
+    for message in messages() {
+        writer.write(&message).unwrap();
+    }
+    let bytes = writer.into_inner();
+
+    let mut reader = FrameReader::with_config(bytes.as_slice(), config);
+    for message in messages() {
+        assert_eq!(reader.read::<Message>().unwrap().unwrap(), message);
+    }
+    assert!(reader.read::<Message>().unwrap().is_none());
+    bytes
+}
+
+#[test]
+fn test_length_prefixes() {
+    let payload: usize = messages()
+        .iter()
+        .map(|message| 16 + message.body.len())
+        .sum();
+
+    let u32 = roundtrip(FrameConfig::new().length_prefix(LengthPrefix::U32));
+    assert_eq!(u32.len(), payload + 4 * 4);
+    let u64 = roundtrip(FrameConfig::new().length_prefix(LengthPrefix::U64));
+    assert_eq!(u64.len(), payload + 4 * 8);
+    // 16, 17, 316 and 70016 take 1, 1, 2 and 3 bytes.
+    let varint = roundtrip(FrameConfig::new().length_prefix(LengthPrefix::Varint));
+    assert_eq!(varint.len(), payload + 7);
+}
+
+#[test]
+fn test_max_frame_size() {
+    let config = FrameConfig::new().max_frame_size(100);
+
+    let mut writer = FrameWriter::with_config(Vec::new(), config);
+    writer.write(&messages()[1]).unwrap();
+    let err = writer.write(&messages()[2]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "frame of 316 bytes exceeds the maximum of 100 bytes",
+    );
+    // The rejected frame wrote nothing.
+    let written = writer.into_inner();
+    assert_eq!(written.len(), 8 + 17);
+
+    let mut writer = FrameWriter::new(Vec::new());
+    writer.write(&messages()[2]).unwrap();
+    let bytes = writer.into_inner();
+    let mut reader = FrameReader::with_config(bytes.as_slice(), config);
+    assert!(reader.read::<Message>().is_err());
+}
+
+#[test]
+fn test_truncated() {
+    for length_prefix in [LengthPrefix::U32, LengthPrefix::U64, LengthPrefix::Varint] {
+        let config = FrameConfig::new().length_prefix(length_prefix);
+        let mut writer = FrameWriter::with_config(Vec::new(), config);
+        writer.write(&messages()[2]).unwrap();
+        let bytes = writer.into_inner();
+
+        for len in 1..bytes.len() {
+            let mut reader = FrameReader::with_config(&bytes[..len], config);
+            assert!(reader.read::<Message>().is_err());
+        }
+    }
+}
+
+#[test]
+fn test_trailing_bytes() {
+    let mut writer = FrameWriter::new(Vec::new());
+    writer.write(&(&messages()[1], 0u8)).unwrap();
+    let bytes = writer.into_inner();
+
+    let mut reader = FrameReader::new(bytes.as_slice());
+    let err = reader.read::<Message>().unwrap_err();
+    assert_eq!(err.to_string(), "trailing bytes in frame");
+}
+
+#[cfg(feature = "crc32fast")]
+#[test]
+fn test_checksum() {
+    let config = FrameConfig::new().checksum(Checksum::Crc32);
+    let mut bytes = roundtrip(config);
+
+    bytes[20] ^= 1;
+    let mut reader = FrameReader::with_config(bytes.as_slice(), config);
+    let err = reader.read::<Message>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ChecksumMismatch);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_socket() {
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+
+    let (tx, rx) = UnixStream::pair().unwrap();
+    let sender = thread::spawn(move || {
+        let mut writer = FrameWriter::new(tx);
+        for message in messages() {
+            writer.write(&message).unwrap();
+        }
+    });
+
+    let mut reader = FrameReader::new(rx);
+    for message in messages() {
+        assert_eq!(reader.read::<Message>().unwrap().unwrap(), message);
+    }
+    sender.join().unwrap();
+    assert!(reader.read::<Message>().unwrap().is_none());
+}
+
+#[test]
+fn test_varint_overflow() {
+    let config = FrameConfig::new().length_prefix(LengthPrefix::Varint);
+    let bytes = [0xFF; 10];
+    let mut reader = FrameReader::with_config(&bytes[..], config);
+    let err = reader.read::<Message>().unwrap_err();
+    assert_eq!(err.to_string(), "varint overflows u64");
+}
+
+// `serialize_framed` and framed `StreamDeserializer` are the default config.
+#[test]
+fn test_default_config_interop() {
+    let mut bytes = Vec::new();
+    for message in messages() {
+        serde_bench::serialize_framed(&mut bytes, &message).unwrap();
+    }
+    let mut reader = FrameReader::new(bytes.as_slice());
+    for message in messages() {
+        assert_eq!(reader.read::<Message>().unwrap().unwrap(), message);
+    }
+    assert!(reader.read::<Message>().unwrap().is_none());
+
+    let mut writer = FrameWriter::new(Vec::new());
+    for message in messages() {
+        writer.write(&message).unwrap();
+    }
+    let written = writer.into_inner();
+    assert_eq!(written, bytes);
+    let stream = StreamDeserializer::<_, Message>::from_slice(&written).framed();
+    let decoded: Vec<Message> = stream.collect::<Result<_, _>>().unwrap();
+    assert_eq!(decoded, messages());
+}
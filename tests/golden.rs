@@ -0,0 +1,171 @@
+#![allow(clippy::derive_partial_eq_without_eq, clippy::unreadable_literal)]
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_bench::{Config, Width};
+use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+#[cfg(target_endian = "little")]
+const VECTORS: &str = include_str!("golden/little-endian.txt");
+#[cfg(target_endian = "big")]
+const VECTORS: &str = include_str!("golden/big-endian.txt");
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct UnitStruct;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct NewtypeStruct(u16);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct TupleStruct(u8, char);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Struct {
+    a: u32,
+    b: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Enum {
+    Unit,
+    Newtype(u64),
+    Tuple(u8, char),
+    Struct { a: u16, b: Option<bool> },
+}
+
+type Check = Box<dyn Fn(&str, &[u8])>;
+
+fn case<T>(value: T) -> Check
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug + 'static,
+{
+    case_with(Config::new(), value)
+}
+
+fn case_with<T>(config: Config, value: T) -> Check
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug + 'static,
+{
+    Box::new(move |name, expected| {
+        let mut bytes = Vec::new();
+        serde_bench::serialize_with_config(&mut bytes, &value, config).unwrap();
+        assert_eq!(bytes, expected, "{name}: serialized {value:?}");
+
+        let deserialized: T = serde_bench::deserialize_with_config(expected, config).unwrap();
+        assert_eq!(deserialized, value, "{name}: deserialized");
+    })
+}
+
+fn cases() -> BTreeMap<&'static str, Check> {
+    let mut map = BTreeMap::new();
+    map.insert("a".to_owned(), 1u8);
+    map.insert("b".to_owned(), 2u8);
+    let length = |width| Config::new().length(width);
+    let variant_index = |width| Config::new().variant_index(width);
+    let varint = Config::new()
+        .variant_index(Width::Varint)
+        .length(Width::Varint);
+
+    BTreeMap::from([
+        ("bool_false", case(false)),
+        ("bool_true", case(true)),
+        ("i8", case(-2i8)),
+        ("i16", case(-2i16)),
+        ("i32", case(-2i32)),
+        ("i64", case(-2i64)),
+        ("i128", case(-2i128)),
+        ("u8", case(200u8)),
+        ("u16", case(0x1234u16)),
+        ("u32", case(0x12345678u32)),
+        ("u64", case(0x0123456789abcdefu64)),
+        ("u128", case(0x0123456789abcdef0011223344556677u128)),
+        ("f32", case(1.5f32)),
+        ("f64", case(-0.25f64)),
+        ("char_1", case('a')),
+        ("char_2", case('\u{e9}')),
+        ("char_3", case('\u{20ac}')),
+        ("char_4", case('\u{1f980}')),
+        ("str_empty", case(String::new())),
+        ("str", case("hello".to_owned())),
+        ("bytes", case(ByteBuf::from(b"\x00\xff".to_vec()))),
+        ("option_none", case(None::<u8>)),
+        ("option_some", case(Some(7u32))),
+        ("unit", case(())),
+        ("unit_struct", case(UnitStruct)),
+        ("newtype_struct", case(NewtypeStruct(3))),
+        ("tuple_struct", case(TupleStruct(1, 'b'))),
+        (
+            "struct",
+            case(Struct {
+                a: 1,
+                b: "xy".to_owned(),
+            }),
+        ),
+        ("tuple", case((1u8, -1i16, true))),
+        ("array", case([1u16, 2, 3])),
+        ("seq_empty", case(Vec::<u32>::new())),
+        ("seq", case(vec![1u32, 2])),
+        ("map", case(map.clone())),
+        ("unit_variant", case(Enum::Unit)),
+        ("newtype_variant", case(Enum::Newtype(5))),
+        ("tuple_variant", case(Enum::Tuple(1, 'c'))),
+        ("struct_variant", case(Enum::Struct { a: 1, b: None })),
+        ("nested", case(vec![Some(Enum::Newtype(9)), None])),
+        (
+            "cfg_u8_str",
+            case_with(length(Width::U8), "hello".to_owned()),
+        ),
+        ("cfg_u16_seq", case_with(length(Width::U16), vec![1u32, 2])),
+        ("cfg_u32_map", case_with(length(Width::U32), map)),
+        (
+            "cfg_u8_variant",
+            case_with(variant_index(Width::U8), Enum::Struct { a: 1, b: None }),
+        ),
+        (
+            "cfg_u16_variant",
+            case_with(variant_index(Width::U16), Enum::Newtype(5)),
+        ),
+        (
+            "cfg_varint",
+            case_with(varint, vec![Some(Enum::Newtype(9)), None]),
+        ),
+    ])
+}
+
+fn parse_hex(name: &str, groups: &[&str]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for group in groups {
+        if *group == "-" {
+            continue;
+        }
+        assert!(group.len() % 2 == 0, "{name}: odd-length hex group {group}");
+        for i in (0..group.len()).step_by(2) {
+            let byte = u8::from_str_radix(&group[i..i + 2], 16)
+                .unwrap_or_else(|_| panic!("{name}: invalid hex group {group}"));
+            bytes.push(byte);
+        }
+    }
+    bytes
+}
+
+#[test]
+fn test_golden() {
+    let mut cases = cases();
+
+    for line in VECTORS.lines() {
+        let line = line.split('#').next().unwrap();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((name, groups)) = words.split_first() else {
+            continue;
+        };
+        let check = cases
+            .remove(name)
+            .unwrap_or_else(|| panic!("{name}: no such case in tests/golden.rs"));
+        check(name, &parse_hex(name, groups));
+    }
+
+    let missing: Vec<&str> = cases.into_keys().collect();
+    assert!(missing.is_empty(), "no golden vectors for {missing:?}");
+}
@@ -0,0 +1,44 @@
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+struct Record {
+    name: String,
+    samples: Vec<u64>,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_deserialize_in_place() {
+    let record = Record {
+        name: "record".to_owned(),
+        samples: vec![1, 2, 3],
+        tags: vec!["a".to_owned(), "b".to_owned()],
+    };
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &record).unwrap();
+
+    let mut place = Record {
+        name: String::with_capacity(64),
+        samples: vec![9; 16],
+        tags: vec!["stale".to_owned(); 4],
+    };
+    let name = place.name.as_ptr();
+    let samples = place.samples.as_ptr();
+    serde_bench::deserialize_in_place(&bytes, &mut place).unwrap();
+
+    assert_eq!(place, record);
+    assert_eq!(place.name.as_ptr(), name);
+    assert_eq!(place.samples.as_ptr(), samples);
+}
+
+#[test]
+fn test_deserialize_in_place_error() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &vec![1u64, 2]).unwrap();
+    bytes.pop();
+
+    let mut place = Vec::<u64>::new();
+    assert!(serde_bench::deserialize_in_place(&bytes, &mut place).is_err());
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "memmap2")]
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Record<'a> {
+    id: u64,
+    name: &'a str,
+    #[serde(with = "serde_bytes")]
+    data: &'a [u8],
+}
+
+// Removed again when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> Self {
+        let file = format!("serde-bench-{}-{name}", std::process::id());
+        let path = std::env::temp_dir().join(file);
+        fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn names() -> Vec<String> {
+    (0..100).map(|i| format!("record {i}")).collect()
+}
+
+fn records(names: &[String]) -> Vec<Record<'_>> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| Record {
+            id: i as u64,
+            name,
+            data: name.as_bytes(),
+        })
+        .collect()
+}
+
+fn contains(outer: &[u8], inner: &[u8]) -> bool {
+    let range = outer.as_ptr_range();
+    range.start <= inner.as_ptr() && inner.as_ptr_range().end <= range.end
+}
+
+#[test]
+fn test_records() {
+    let names = names();
+    let expected = records(&names);
+    let mut bytes = Vec::new();
+    for record in &expected {
+        serde_bench::serialize(&mut bytes, record).unwrap();
+    }
+    let file = TempFile::new("records", &bytes);
+
+    let mapped = unsafe { serde_bench::open_file(&file.0) }.unwrap();
+    let decoded: Vec<Record> = mapped.records().collect::<Result<_, _>>().unwrap();
+    assert_eq!(decoded, expected);
+    for record in &decoded {
+        assert!(contains(mapped.as_bytes(), record.name.as_bytes()));
+        assert!(contains(mapped.as_bytes(), record.data));
+    }
+}
+
+#[test]
+fn test_framed_records() {
+    let names = names();
+    let expected = records(&names);
+    let mut bytes = Vec::new();
+    for record in &expected {
+        serde_bench::serialize_framed(&mut bytes, record).unwrap();
+    }
+    let file = TempFile::new("framed", &bytes);
+
+    let mapped = unsafe { serde_bench::open_file(&file.0) }.unwrap();
+    let decoded: Vec<Record> = mapped.records().framed().collect::<Result<_, _>>().unwrap();
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn test_deserialize() {
+    let names = names();
+    let expected = records(&names);
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &expected).unwrap();
+    let file = TempFile::new("single", &bytes);
+
+    let mmap = unsafe { memmap2::Mmap::map(&fs::File::open(&file.0).unwrap()) }.unwrap();
+    let mapped = serde_bench::MappedFile::from_mmap(mmap);
+    let decoded: Vec<Record> = mapped.deserialize().unwrap();
+    assert_eq!(decoded, expected);
+    assert!(contains(mapped.as_bytes(), decoded[0].name.as_bytes()));
+}
+
+#[test]
+fn test_empty() {
+    let file = TempFile::new("empty", b"");
+    let mapped = unsafe { serde_bench::open_file(&file.0) }.unwrap();
+    assert_eq!(mapped.records::<Record>().count(), 0);
+    let err = mapped.deserialize::<u64>().unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of input");
+}
+
+#[test]
+fn test_missing() {
+    let path = std::env::temp_dir().join("serde-bench-missing-file");
+    let err = unsafe { serde_bench::open_file(path) }.err().unwrap();
+    assert_eq!(err.kind(), serde_bench::ErrorKind::Io);
+}
@@ -0,0 +1,121 @@
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::{Deserialize, Serialize};
+use serde_bench::StreamDeserializer;
+use std::io;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Record {
+    id: u32,
+    message: String,
+    tags: Vec<char>,
+}
+
+fn records() -> Vec<Record> {
+    (0..3)
+        .map(|id| Record {
+            id,
+            message: format!("record {id}"),
+            tags: vec!['a', '\u{e9}', '\u{1f980}'],
+        })
+        .collect()
+}
+
+// Hands out one byte per read call.
+struct Trickle<'a>(&'a [u8]);
+
+impl io::Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.0.len()).min(1);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_slice() {
+    let mut bytes = Vec::new();
+    for record in records() {
+        serde_bench::serialize(&mut bytes, &record).unwrap();
+    }
+
+    let stream = StreamDeserializer::<_, Record>::from_slice(&bytes);
+    let decoded: Vec<Record> = stream.collect::<serde_bench::Result<_>>().unwrap();
+    assert_eq!(decoded, records());
+}
+
+#[test]
+fn test_slice_borrowed() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &"one").unwrap();
+    serde_bench::serialize(&mut bytes, &"two").unwrap();
+
+    let stream = StreamDeserializer::<_, &str>::from_slice(&bytes);
+    let decoded: Vec<&str> = stream.collect::<serde_bench::Result<_>>().unwrap();
+    assert_eq!(decoded, ["one", "two"]);
+}
+
+#[test]
+fn test_reader() {
+    let mut bytes = Vec::new();
+    for record in records() {
+        serde_bench::serialize(&mut bytes, &record).unwrap();
+    }
+
+    let stream = StreamDeserializer::<_, Record>::from_reader(Trickle(&bytes));
+    let decoded: Vec<Record> = stream.collect::<serde_bench::Result<_>>().unwrap();
+    assert_eq!(decoded, records());
+}
+
+#[test]
+fn test_truncated() {
+    let mut bytes = Vec::new();
+    for record in records() {
+        serde_bench::serialize(&mut bytes, &record).unwrap();
+    }
+    bytes.pop();
+
+    for mut stream in [
+        Box::new(StreamDeserializer::<_, Record>::from_slice(&bytes))
+            as Box<dyn Iterator<Item = _>>,
+        Box::new(StreamDeserializer::<_, Record>::from_reader(Trickle(
+            &bytes,
+        ))),
+    ] {
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+}
+
+#[test]
+fn test_framed() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_framed(&mut bytes, &records()[0]).unwrap();
+    // A frame holding something that is not a Record.
+    serde_bench::serialize_framed(&mut bytes, &(7u32, 1u64 << 60)).unwrap();
+    // A frame with a byte left over after the Record.
+    serde_bench::serialize_framed(&mut bytes, &(&records()[1], 0u8)).unwrap();
+    serde_bench::serialize_framed(&mut bytes, &records()[2]).unwrap();
+
+    for stream in [
+        Box::new(StreamDeserializer::<_, Record>::from_slice(&bytes).framed())
+            as Box<dyn Iterator<Item = _>>,
+        Box::new(StreamDeserializer::<_, Record>::from_reader(Trickle(&bytes)).framed()),
+    ] {
+        let results: Vec<_> = stream.collect();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), &records()[0]);
+        assert_eq!(
+            results[1].as_ref().unwrap_err().to_string(),
+            "unexpected end of frame",
+        );
+        assert_eq!(
+            results[2].as_ref().unwrap_err().to_string(),
+            "trailing bytes in frame",
+        );
+        assert_eq!(results[3].as_ref().unwrap(), &records()[2]);
+    }
+}
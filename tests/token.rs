@@ -0,0 +1,288 @@
+#![cfg(feature = "test-util")]
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::{Deserialize, Serialize};
+use serde_bench::token::{
+    assert_de_tokens, assert_de_tokens_borrowed, assert_de_tokens_error, assert_ser_tokens_error,
+    assert_tokens, Token,
+};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Struct {
+    a: u32,
+    b: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct UnitStruct;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct NewtypeStruct(i64);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct TupleStruct(u8, char);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Enum {
+    Unit,
+    Newtype(u16),
+    Tuple(bool, f32),
+    Struct { x: i8, y: f64 },
+}
+
+#[test]
+fn test_primitives() {
+    assert_tokens(&true, &[Token::Bool(true)]);
+    assert_tokens(&-1i8, &[Token::I8(-1)]);
+    assert_tokens(&u128::MAX, &[Token::U128(u128::MAX)]);
+    assert_tokens(&1.5f64, &[Token::F64(1.5)]);
+    assert_tokens(&'\u{e9}', &[Token::Char('\u{e9}')]);
+    assert_tokens(&"str".to_owned(), &[Token::Str("str")]);
+    assert_tokens(&(), &[Token::Unit]);
+}
+
+#[test]
+fn test_bytes() {
+    assert_tokens(
+        &serde_bytes::ByteBuf::from(b"\x00\xFF".to_vec()),
+        &[Token::Bytes(b"\x00\xFF")],
+    );
+}
+
+#[test]
+fn test_bulk_array() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Bulk {
+        #[serde(with = "serde_bench::bulk::array")]
+        bytes: [u8; 3],
+    }
+
+    assert_tokens(
+        &Bulk { bytes: [1, 2, 3] },
+        &[
+            Token::Struct {
+                name: "Bulk",
+                len: 1,
+            },
+            Token::Raw(&[1, 2, 3]),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_bulk_vec() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Bulk {
+        #[serde(with = "serde_bench::bulk")]
+        bytes: Vec<u8>,
+    }
+
+    assert_tokens(
+        &Bulk { bytes: vec![1, 2] },
+        &[
+            Token::Struct {
+                name: "Bulk",
+                len: 1,
+            },
+            Token::Tuple { len: 2 },
+            Token::Len(2),
+            Token::Raw(&[1, 2]),
+            Token::TupleEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_option() {
+    assert_tokens(&None::<u32>, &[Token::None]);
+    assert_tokens(&Some(7u32), &[Token::Some, Token::U32(7)]);
+}
+
+#[test]
+fn test_struct() {
+    assert_tokens(
+        &Struct {
+            a: 1,
+            b: Some("b".to_owned()),
+        },
+        &[
+            Token::Struct {
+                name: "Struct",
+                len: 2,
+            },
+            Token::U32(1),
+            Token::Some,
+            Token::Str("b"),
+            Token::StructEnd,
+        ],
+    );
+    assert_tokens(&UnitStruct, &[Token::UnitStruct { name: "UnitStruct" }]);
+    assert_tokens(
+        &NewtypeStruct(-2),
+        &[
+            Token::NewtypeStruct {
+                name: "NewtypeStruct",
+            },
+            Token::I64(-2),
+        ],
+    );
+    assert_tokens(
+        &TupleStruct(3, 'c'),
+        &[
+            Token::TupleStruct {
+                name: "TupleStruct",
+                len: 2,
+            },
+            Token::U8(3),
+            Token::Char('c'),
+            Token::TupleStructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_enum() {
+    assert_tokens(
+        &Enum::Unit,
+        &[Token::UnitVariant {
+            name: "Enum",
+            variant_index: 0,
+        }],
+    );
+    assert_tokens(
+        &Enum::Newtype(9),
+        &[
+            Token::NewtypeVariant {
+                name: "Enum",
+                variant_index: 1,
+            },
+            Token::U16(9),
+        ],
+    );
+    assert_tokens(
+        &Enum::Tuple(false, 0.5),
+        &[
+            Token::TupleVariant {
+                name: "Enum",
+                variant_index: 2,
+                len: 2,
+            },
+            Token::Bool(false),
+            Token::F32(0.5),
+            Token::TupleVariantEnd,
+        ],
+    );
+    assert_tokens(
+        &Enum::Struct { x: -3, y: 2.0 },
+        &[
+            Token::StructVariant {
+                name: "Enum",
+                variant_index: 3,
+                len: 2,
+            },
+            Token::I8(-3),
+            Token::F64(2.0),
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_compound() {
+    assert_tokens(
+        &vec![1u64, 2],
+        &[
+            Token::Seq { len: 2 },
+            Token::U64(1),
+            Token::U64(2),
+            Token::SeqEnd,
+        ],
+    );
+    assert_tokens(
+        &(1u8, 'x'),
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(1),
+            Token::Char('x'),
+            Token::TupleEnd,
+        ],
+    );
+    assert_tokens(
+        &[1i32, 2, 3],
+        &[
+            Token::Tuple { len: 3 },
+            Token::I32(1),
+            Token::I32(2),
+            Token::I32(3),
+            Token::TupleEnd,
+        ],
+    );
+    let mut map = BTreeMap::new();
+    map.insert("k".to_owned(), vec![Enum::Unit]);
+    assert_tokens(
+        &map,
+        &[
+            Token::Map { len: 1 },
+            Token::Str("k"),
+            Token::Seq { len: 1 },
+            Token::UnitVariant {
+                name: "Enum",
+                variant_index: 0,
+            },
+            Token::SeqEnd,
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_de_only() {
+    assert_de_tokens(&Box::<str>::from("owned"), &[Token::Str("owned")]);
+}
+
+#[test]
+fn test_borrowed() {
+    assert_de_tokens_borrowed(&"borrowed", &[Token::Str("borrowed")]);
+}
+
+#[test]
+fn test_ser_mismatch() {
+    assert_ser_tokens_error(
+        &1u32,
+        &[Token::U64(1)],
+        "expected Token::U64(1) but serialized as U32(1)",
+    );
+    assert_ser_tokens_error(
+        &Some(1u32),
+        &[Token::Some],
+        "expected end of tokens, but U32(1) was serialized",
+    );
+}
+
+#[test]
+fn test_de_mismatch() {
+    assert_de_tokens_error::<u32>(&[Token::U64(1)], "expected Token::U32, found Token::U64(1)");
+    assert_de_tokens_error::<Vec<u8>>(
+        &[Token::Seq { len: 2 }, Token::U8(1)],
+        "ran out of tokens to deserialize",
+    );
+    assert_de_tokens_error::<(u8,)>(
+        &[
+            Token::Tuple { len: 1 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::TupleEnd,
+        ],
+        "expected Token::TupleEnd, found Token::U8(2)",
+    );
+    assert_de_tokens_error::<Enum>(
+        &[Token::NewtypeVariant {
+            name: "Enum",
+            variant_index: 0,
+        }],
+        "expected Token::UnitVariant, found Token::NewtypeVariant { name: \"Enum\", variant_index: 0 }",
+    );
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Record {
+    id: u32,
+    name: String,
+    score: Option<f64>,
+    tags: Vec<Tag>,
+    attrs: BTreeMap<String, i64>,
+    wrapper: Wrapper,
+    unit: (),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Tag {
+    Plain,
+    Weight(u8),
+    Pair(char, bool),
+    Range { lo: i16, hi: i16 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Wrapper(u128);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Samples {
+    id: u8,
+    #[serde(with = "serde_bench::bulk::array")]
+    points: [u16; 3],
+    #[serde(with = "serde_bench::bulk")]
+    values: Vec<i32>,
+}
+
+fn record() -> Record {
+    let mut attrs = BTreeMap::new();
+    attrs.insert("a".to_owned(), -1);
+    attrs.insert("b".to_owned(), 2);
+    Record {
+        id: 7,
+        name: "seven".to_owned(),
+        score: Some(0.5),
+        tags: vec![
+            Tag::Plain,
+            Tag::Weight(3),
+            Tag::Pair('x', true),
+            Tag::Range { lo: -4, hi: 4 },
+        ],
+        attrs,
+        wrapper: Wrapper(1),
+        unit: (),
+    }
+}
+
+fn transcode_to_json<'de, T>(bytes: &'de [u8]) -> serde_bench::Result<String>
+where
+    T: serde::Deserialize<'de>,
+{
+    let mut json = Vec::new();
+    let mut serializer = serde_json::Serializer::new(&mut json);
+    serde_bench::transcode_to::<T, _>(bytes, &mut serializer)?;
+    Ok(String::from_utf8(json).unwrap())
+}
+
+#[test]
+fn test_to_json() {
+    let record = record();
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &record).unwrap();
+
+    let json = transcode_to_json::<Record>(&bytes).unwrap();
+    assert_eq!(json, serde_json::to_string(&record).unwrap());
+}
+
+#[test]
+fn test_from_json() {
+    let record = record();
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, &record).unwrap();
+
+    // Fields out of declaration order.
+    let json = r#"{
+        "tags": ["Plain", {"Weight": 3}, {"Pair": ["x", true]}, {"Range": {"hi": 4, "lo": -4}}],
+        "id": 7,
+        "name": "seven",
+        "score": 0.5,
+        "attrs": {"a": -1, "b": 2},
+        "wrapper": 1,
+        "unit": null
+    }"#;
+    let mut bytes = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    serde_bench::transcode_from::<Record, _>(&mut bytes, &mut deserializer).unwrap();
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn test_bulk() {
+    let samples = Samples {
+        id: 1,
+        points: [1, 2, 3],
+        values: vec![-1, 4],
+    };
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &samples).unwrap();
+
+    let json = transcode_to_json::<Samples>(&bytes).unwrap();
+    assert_eq!(json, serde_json::to_string(&samples).unwrap());
+
+    let mut out = Vec::new();
+    let mut serializer = serde_bench::VecSerializer::new(&mut out);
+    serde_bench::transcode_to::<Samples, _>(&bytes, &mut serializer).unwrap();
+    assert_eq!(out, bytes);
+}
+
+#[test]
+fn test_truncated() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &record()).unwrap();
+    bytes.pop();
+
+    let err = transcode_to_json::<Record>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of input");
+}
+
+#[test]
+fn test_invalid_variant() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &(1u64, 9u32)).unwrap();
+
+    let err = transcode_to_json::<Vec<Tag>>(&bytes).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value: integer `9`, expected variant index 0 <= i < 4",
+    );
+}
+
+#[test]
+fn test_serializer_error() {
+    let mut map = BTreeMap::new();
+    map.insert((1u8, 2u8), 3u8);
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &map).unwrap();
+
+    let err = transcode_to_json::<BTreeMap<(u8, u8), u8>>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), "key must be a string");
+}
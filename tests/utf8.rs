@@ -0,0 +1,53 @@
+use serde_bench::StreamDeserializer;
+
+#[test]
+fn test_valid() {
+    for s in [
+        "",
+        "ascii only",
+        "h\u{e9}llo",
+        "\u{7d20}\u{65e9}\u{3044}",
+        "\u{1f980}",
+    ] {
+        let mut bytes = Vec::new();
+        serde_bench::serialize(&mut bytes, &s).unwrap();
+        let checked: &str = serde_bench::deserialize(&bytes).unwrap();
+        let owned = StreamDeserializer::<_, String>::from_reader(bytes.as_slice())
+            .next()
+            .unwrap()
+            .unwrap();
+        let trusted: &str = unsafe { serde_bench::deserialize_trusted(&bytes) }.unwrap();
+        assert_eq!(checked, s);
+        assert_eq!(owned, s);
+        assert_eq!(trusted, s);
+    }
+}
+
+#[test]
+fn test_invalid() {
+    // A long valid prefix so that a vectorized validator sees full blocks.
+    let mut string = "a".repeat(100).into_bytes();
+    string.extend_from_slice(&[0xe2, 0x28, 0xa1]);
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &serde_bytes::Bytes::new(&string)).unwrap();
+
+    let expected = "invalid utf-8 sequence of 1 bytes from index 100";
+    let err = serde_bench::deserialize::<&str>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+    let err = StreamDeserializer::<_, String>::from_reader(bytes.as_slice())
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.to_string(), expected);
+}
+
+#[test]
+fn test_trusted_truncated() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize(&mut bytes, &("h\u{e9}llo", 1u64)).unwrap();
+
+    for len in 0..bytes.len() {
+        let result = unsafe { serde_bench::deserialize_trusted::<(&str, u64)>(&bytes[..len]) };
+        assert_eq!(result.unwrap_err().to_string(), "unexpected end of input");
+    }
+}
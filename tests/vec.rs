@@ -0,0 +1,80 @@
+use serde::ser::{Error as _, Serializer};
+use serde::Serialize;
+use serde_bench::VecSerializer;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+enum Enum {
+    Unit,
+    Newtype(i8),
+    Tuple(u16, char),
+    Struct { s: String },
+}
+
+#[derive(Serialize)]
+struct Bulk(#[serde(with = "serde_bench::bulk")] Vec<f32>);
+
+fn assert_same<T>(value: &T)
+where
+    T: Serialize,
+{
+    let mut expected = Vec::new();
+    serde_bench::serialize(&mut expected, value).unwrap();
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_size_hint(&mut bytes, value, 0).unwrap();
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn test_same_bytes() {
+    assert_same(&(true, 1u8, -2i8, 3u16, -4i16, 5u32, -6i32, 7u64, -8i64));
+    assert_same(&(9u128, -10i128, 0.5f32, -0.25f64, 'x', '\u{1f980}'));
+    assert_same(&(
+        "str",
+        serde_bytes::Bytes::new(b"bytes"),
+        (),
+        Some(1u8),
+        None::<u8>,
+    ));
+    assert_same(&[Enum::Unit, Enum::Newtype(-1), Enum::Tuple(2, 'c')]);
+    assert_same(&Enum::Struct { s: "s".to_owned() });
+    assert_same(&vec![vec![1u64; 3]; 4]);
+    let map: BTreeMap<String, Option<u16>> = (0..8).map(|i| (format!("{i}"), Some(i))).collect();
+    assert_same(&map);
+    assert_same(&Bulk(vec![1.5; 17]));
+}
+
+#[test]
+fn test_size_hint() {
+    let mut bytes = Vec::new();
+    serde_bench::serialize_with_size_hint(&mut bytes, &[0u64; 4], 100).unwrap();
+    assert_eq!(bytes.len(), 32);
+    assert!(bytes.capacity() >= 100);
+}
+
+#[test]
+fn test_appends() {
+    let mut bytes = vec![0xff];
+    let mut ser = VecSerializer::new(&mut bytes);
+    7u8.serialize(&mut ser).unwrap();
+    assert_eq!(bytes, [0xff, 7]);
+}
+
+#[test]
+fn test_custom_error() {
+    struct Fails;
+
+    impl Serialize for Fails {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let _ = serializer;
+            Err(S::Error::custom("nope"))
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let err = serde_bench::serialize_with_size_hint(&mut bytes, &(1u8, Fails), 0).unwrap_err();
+    assert_eq!(err.to_string(), "nope");
+}